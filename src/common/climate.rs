@@ -0,0 +1,12 @@
+/// Metadata shared by every manufacturer's state type, for tooling generic over protocols.
+pub trait ClimateState {
+    /// The number of bits in one frame of the protocol
+    const FRAME_BITS: usize;
+
+    /// The manufacturer name, e.g. to label frames on a dashboard
+    fn manufacturer() -> &'static str;
+
+    fn frame_bits() -> usize {
+        Self::FRAME_BITS
+    }
+}
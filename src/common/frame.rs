@@ -0,0 +1,52 @@
+use bit_vec::BitVec;
+
+use crate::common::{AsBitVec, InfraredProtocol};
+use crate::elios::{EliosState, ELIOS_IR};
+
+/// A command for any supported manufacturer, so that mixed devices can be driven uniformly.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Frame {
+    Elios(EliosState),
+}
+
+impl Frame {
+    pub fn as_value(self) -> u64 {
+        match self {
+            Frame::Elios(state) => state.as_value(),
+        }
+    }
+
+    /// The infrared timings the frame must be sent with.
+    pub fn protocol(&self) -> &'static InfraredProtocol {
+        match self {
+            Frame::Elios(_) => &ELIOS_IR,
+        }
+    }
+}
+
+impl AsBitVec for Frame {
+    fn as_bitvec(self) -> BitVec {
+        match self {
+            Frame::Elios(state) => state.as_bitvec(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::Temperature;
+
+    #[test]
+    fn given_elios_frame_then_is_encoded_as_elios_state() {
+        let state = EliosState::cool(Temperature::Celcius(22));
+        let frame = Frame::Elios(state);
+
+        assert_eq!(frame.as_value(), state.as_value());
+        assert_eq!(frame.as_bitvec(), state.as_bitvec());
+        assert_eq!(
+            frame.protocol().encode(frame.as_bitvec()),
+            ELIOS_IR.encode(state.as_bitvec())
+        );
+    }
+}
@@ -1,5 +1,7 @@
 use bit_vec::BitVec;
+use std::convert::TryFrom;
 
+#[derive(Debug, Copy, Clone)]
 pub struct InfraredProtocol {
     /// The duration of the beginning pulse in microseconds
     pub leading_pulse: u32,
@@ -13,9 +15,88 @@ pub struct InfraredProtocol {
     pub zero_pulse: u32,
     /// The duration of the gap in microseconds when sending a logical 0
     pub zero_gap: u32,
+    /// The modulation frequency of the pulses in hertz
+    pub carrier_hz: u32,
+    /// Called with the bits and resulting timings of every `encode`, e.g. to forward them to
+    /// `log` or `tracing`
+    pub logger: Option<fn(&BitVec, &[u32])>,
+}
+
+/// How far a duration may stray from `nominal` and still resemble it: 25%.
+fn resemblance_tolerance(nominal: u32) -> u32 {
+    nominal / 4
+}
+
+/// The on-disk form of an `InfraredProtocol`, read by `InfraredProtocol::from_toml`.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProtocolDefinition {
+    leading_pulse: u32,
+    leading_gap: u32,
+    one_pulse: u32,
+    one_gap: u32,
+    zero_pulse: u32,
+    zero_gap: u32,
+    carrier_hz: u32,
+}
+
+/// The decoded bits along with the measured leading durations, to assess signal quality.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodeReport {
+    pub bits: BitVec,
+    /// The measured duration of the leading pulse in microseconds
+    pub leading_pulse: u32,
+    /// The measured duration of the leading gap in microseconds
+    pub leading_gap: u32,
+    /// The protocol's nominal leading pulse in microseconds
+    pub nominal_leading_pulse: u32,
+    /// The protocol's nominal leading gap in microseconds
+    pub nominal_leading_gap: u32,
 }
 
 impl InfraredProtocol {
+    /// Reads a protocol definition from a TOML document holding the six timings in microseconds
+    /// and `carrier_hz` as top-level keys. Returns `None` if the document is not valid TOML, or
+    /// if any key is missing, unknown or not a `u32`.
+    #[cfg(feature = "serde")]
+    pub fn from_toml(s: &str) -> Option<InfraredProtocol> {
+        let definition: ProtocolDefinition = toml::from_str(s).ok()?;
+
+        Some(InfraredProtocol {
+            leading_pulse: definition.leading_pulse,
+            leading_gap: definition.leading_gap,
+            one_pulse: definition.one_pulse,
+            one_gap: definition.one_gap,
+            zero_pulse: definition.zero_pulse,
+            zero_gap: definition.zero_gap,
+            carrier_hz: definition.carrier_hz,
+            logger: None,
+        })
+    }
+
+    /// The modulation frequency of the pulses in hertz.
+    pub fn carrier_hz(&self) -> u32 {
+        self.carrier_hz
+    }
+
+    /// A copy of the protocol modulated at a different carrier frequency.
+    pub fn with_carrier_hz(&self, carrier_hz: u32) -> InfraredProtocol {
+        InfraredProtocol {
+            carrier_hz,
+            ..*self
+        }
+    }
+
+    /// A copy of the protocol with different leading timings, e.g. to tune a picky AGC.
+    pub fn with_leading(&self, leading_pulse: u32, leading_gap: u32) -> InfraredProtocol {
+        InfraredProtocol {
+            leading_pulse,
+            leading_gap,
+            ..*self
+        }
+    }
+
     pub fn encode(&self, data: BitVec) -> Vec<u32> {
         let mut buffer = Vec::new();
 
@@ -32,8 +113,413 @@ impl InfraredProtocol {
             }
         }
 
+        if let Some(logger) = self.logger {
+            logger(&data, &buffer);
+        }
+
+        buffer
+    }
+
+    /// Same as `encode`, with marks as positive and spaces as negative durations.
+    pub fn encode_signed(&self, data: BitVec) -> Vec<i32> {
+        self.encode(data)
+            .iter()
+            .enumerate()
+            .map(|(i, duration)| {
+                if i % 2 == 0 {
+                    *duration as i32
+                } else {
+                    -(*duration as i32)
+                }
+            })
+            .collect()
+    }
+
+    /// A frame without data bits: the lead followed by a stop pulse ending the leading gap. Sent
+    /// between real commands to keep a receiver's AGC settled. Receivers discard it.
+    pub fn ping_frame(&self) -> Vec<u32> {
+        vec![self.leading_pulse, self.leading_gap, self.zero_pulse]
+    }
+
+    /// `repeats` copies of the frame separated by gaps of `base_gap_us` randomly varied by up to
+    /// `jitter_us` either way, to avoid collisions between blasters sharing a room. The gaps come
+    /// from a deterministic generator seeded with `seed`, and are merged into the last gap of the
+    /// preceding frame so pulses and gaps keep alternating.
+    pub fn encode_repeated_jittered(
+        &self,
+        data: BitVec,
+        repeats: usize,
+        base_gap_us: u32,
+        jitter_us: u32,
+        seed: u64,
+    ) -> Vec<u32> {
+        let frame = self.encode(data);
+        let mut state = seed;
+        let mut buffer = Vec::new();
+
+        for i in 0..repeats {
+            if i > 0 {
+                let offset = (splitmix64(&mut state) % (2 * jitter_us as u64 + 1)) as i64;
+                let gap = (base_gap_us as i64 + offset - jitter_us as i64).max(0) as u32;
+
+                if let Some(last) = buffer.last_mut() {
+                    *last += gap;
+                }
+            }
+
+            buffer.extend_from_slice(&frame);
+        }
+
         buffer
     }
+
+    /// Same as `encode`, with `guard_us` added to the gap of every data bit for receivers that
+    /// need extra settling time.
+    pub fn encode_with_guard(&self, data: BitVec, guard_us: u32) -> Vec<u32> {
+        let mut buffer = self.encode(data);
+
+        for gap in buffer.iter_mut().skip(3).step_by(2) {
+            *gap += guard_us;
+        }
+
+        buffer
+    }
+
+    /// Same as `encode`, lengthening the gap after each full byte but the last by `byte_gap_us`
+    /// for variants that space their bytes out. Marks and spaces still alternate.
+    pub fn encode_byte_spaced(&self, data: BitVec, byte_gap_us: u32) -> Vec<u32> {
+        let bits = data.len();
+        let mut buffer = self.encode(data);
+
+        for byte_end in (8..bits).step_by(8) {
+            buffer[2 * byte_end + 1] += byte_gap_us;
+        }
+
+        buffer
+    }
+
+    /// Same as `encode`, preceded by `preamble_bits` alternating 1/0 bit timings to let a
+    /// marginal receiver lock on before the leading pulse.
+    pub fn encode_with_preamble(&self, data: BitVec, preamble_bits: usize) -> Vec<u32> {
+        let mut buffer = Vec::new();
+
+        for i in 0..preamble_bits {
+            if i % 2 == 0 {
+                buffer.push(self.one_pulse);
+                buffer.push(self.one_gap);
+            } else {
+                buffer.push(self.zero_pulse);
+                buffer.push(self.zero_gap);
+            }
+        }
+
+        buffer.extend(self.encode(data));
+
+        buffer
+    }
+
+    /// Same as `encode`, with every duration rounded to the nearest multiple of `step_us` to
+    /// preview what a coarse blaster emits. Halfway values round down, so 550 becomes 500 with
+    /// a 100µs step. `step_us` must not be zero.
+    pub fn encode_quantized(&self, data: BitVec, step_us: u32) -> Vec<u32> {
+        self.encode(data)
+            .iter()
+            .map(|duration| (duration + (step_us - 1) / 2) / step_us * step_us)
+            .collect()
+    }
+
+    /// The carrier on/off transitions of the encoded frame, as `(level, at_us)` pairs with
+    /// absolute timestamps starting at 0.
+    pub fn to_transitions(&self, data: BitVec) -> Vec<(bool, u64)> {
+        let mut at = 0;
+
+        self.encode(data)
+            .iter()
+            .enumerate()
+            .map(|(i, duration)| {
+                let transition = (i % 2 == 0, at);
+                at += *duration as u64;
+                transition
+            })
+            .collect()
+    }
+
+    /// The encoded frame as a C array declaration and length macro, ready to paste into an
+    /// Arduino sketch calling `IRsend::sendRaw`.
+    pub fn to_arduino_array(&self, name: &str, data: BitVec) -> String {
+        let timings = self.encode(data);
+        let values: Vec<String> = timings.iter().map(|x| x.to_string()).collect();
+
+        format!(
+            "const uint16_t {}[] = {{{}}};\n#define {}_LENGTH {}\n",
+            name,
+            values.join(", "),
+            name.to_uppercase(),
+            timings.len()
+        )
+    }
+
+    /// The encoded frame as a learned Pronto code: the `0000` preamble, the carrier frequency
+    /// word, the once and repeat burst pair counts, then every duration in carrier cycles. The
+    /// frame is sent once, so there are no repeat pairs. Returns `None` if the carrier does not
+    /// fit the 16-bit frequency word, e.g. a zero carrier or one below about 64 Hz.
+    pub fn to_pronto(&self, data: BitVec) -> Option<Vec<u16>> {
+        let carrier_hz = self.carrier_hz as u64;
+
+        // the frequency word counts periods of the 0.241246 µs Pronto clock, and receivers derive
+        // the carrier period from that rounded word, so durations are converted with it too
+        let frequency = (4_145_146 + carrier_hz / 2).checked_div(carrier_hz)?;
+        let frequency = u16::try_from(frequency).ok().filter(|word| *word != 0)?;
+        let period = frequency as u64 * 241_246;
+
+        let timings = self.encode(data);
+        let mut words = vec![0x0000, frequency, (timings.len() / 2) as u16, 0];
+        words.extend(
+            timings
+                .iter()
+                .map(|us| ((*us as u64 * 1_000_000 + period / 2) / period) as u16),
+        );
+
+        Some(words)
+    }
+
+    /// The encoded frame as ESP32 RMT items `(duration0, level0, duration1, level1)`, each a mark
+    /// with the carrier on followed by its space, assuming a 1 µs RMT tick. Durations saturate
+    /// at the 15 bits an item can hold.
+    pub fn to_rmt_items(&self, data: BitVec) -> Vec<(u16, bool, u16, bool)> {
+        let ticks = |us: u32| us.min(0x7fff) as u16;
+
+        self.encode(data)
+            .chunks(2)
+            .map(|pair| (ticks(pair[0]), true, ticks(pair[1]), false))
+            .collect()
+    }
+
+    /// The encoded frame as LIRC raw text, one `pulse` or `space` line per duration. `encode`
+    /// always ends on a bit gap, so every pulse line is followed by its space.
+    pub fn to_lirc(&self, data: BitVec) -> String {
+        self.encode(data)
+            .iter()
+            .enumerate()
+            .map(|(i, duration)| {
+                let kind = if i % 2 == 0 { "pulse" } else { "space" };
+
+                format!("{} {}\n", kind, duration)
+            })
+            .collect()
+    }
+
+    /// Whether captures can be told apart unambiguously with the 25% tolerance of `looks_valid`:
+    /// ones and zeros, and the leading pulse and bit pulses, must be far enough apart that no
+    /// duration resembles both.
+    pub fn self_consistent(&self) -> bool {
+        let apart =
+            |a: u32, b: u32| a.abs_diff(b) > resemblance_tolerance(a) + resemblance_tolerance(b);
+
+        let bits = apart(self.one_pulse, self.zero_pulse) || apart(self.one_gap, self.zero_gap);
+        let leading =
+            apart(self.leading_pulse, self.one_pulse) && apart(self.leading_pulse, self.zero_pulse);
+
+        bits && leading
+    }
+
+    /// Whether frames of the two protocols cannot be mistaken for one another when decoding with
+    /// `tolerance_us`: either their leading pulses or gaps are too far apart to overlap, or none
+    /// of the bit symbols of one protocol falls within the windows of the other.
+    pub fn is_distinguishable_from(&self, other: &InfraredProtocol, tolerance_us: u32) -> bool {
+        let apart = |a: u32, b: u32| a.abs_diff(b) > 2 * tolerance_us;
+
+        let leads = apart(self.leading_pulse, other.leading_pulse)
+            || apart(self.leading_gap, other.leading_gap);
+
+        let symbols = [
+            (self.one_pulse, self.one_gap),
+            (self.zero_pulse, self.zero_gap),
+        ];
+        let other_symbols = [
+            (other.one_pulse, other.one_gap),
+            (other.zero_pulse, other.zero_gap),
+        ];
+
+        let bits = symbols.iter().all(|(pulse, gap)| {
+            other_symbols.iter().all(|(other_pulse, other_gap)| {
+                apart(*pulse, *other_pulse) || apart(*gap, *other_gap)
+            })
+        });
+
+        leads || bits
+    }
+
+    /// Cheap pre-filter run before decoding: the capture must start with something resembling the
+    /// lead, and every later pulse must resemble a bit pulse, where resembling means within 25% of
+    /// the nominal duration. A trailing stop pulse is allowed.
+    pub fn looks_valid(&self, timings: &[u32]) -> bool {
+        let resembles = |duration: u32, nominal: u32| {
+            duration.abs_diff(nominal) <= resemblance_tolerance(nominal)
+        };
+
+        timings.len() >= 2
+            && resembles(timings[0], self.leading_pulse)
+            && resembles(timings[1], self.leading_gap)
+            && timings[2..].iter().step_by(2).all(|pulse| {
+                resembles(*pulse, self.one_pulse) || resembles(*pulse, self.zero_pulse)
+            })
+    }
+
+    /// Number of durations `encode` outputs for `bit_count` bits: the leading pulse and gap, then
+    /// a pulse and a gap per bit.
+    pub fn timing_len(bit_count: usize) -> usize {
+        2 + 2 * bit_count
+    }
+
+    /// Size in bytes of the `encode` output for `bit_count` bits, to size transport buffers.
+    pub fn encoded_byte_size(bit_count: usize) -> usize {
+        Self::timing_len(bit_count) * std::mem::size_of::<u32>()
+    }
+
+    /// The time it takes to send `data` in microseconds.
+    pub fn duration_us(&self, data: &BitVec) -> u64 {
+        self.encode(data.clone()).iter().map(|x| *x as u64).sum()
+    }
+
+    /// Decodes alternating pulse/gap durations back into bits. Every duration must be within
+    /// `tolerance_us` of its nominal value. A trailing stop pulse after the last gap is skipped.
+    pub fn decode(&self, timings: &[u32], tolerance_us: u32) -> Option<BitVec> {
+        let matches = |duration: u32, nominal: u32| duration.abs_diff(nominal) <= tolerance_us;
+
+        let timings = match timings.split_last() {
+            Some((&stop, rest)) if !timings.len().is_multiple_of(2) => {
+                if !matches(stop, self.one_pulse) && !matches(stop, self.zero_pulse) {
+                    return None;
+                }
+
+                rest
+            }
+            _ => timings,
+        };
+
+        if timings.len() < 2 {
+            return None;
+        }
+
+        if !matches(timings[0], self.leading_pulse) || !matches(timings[1], self.leading_gap) {
+            return None;
+        }
+
+        let threshold = self.bit_threshold_us();
+        let mut bits = BitVec::new();
+
+        for pair in timings[2..].chunks(2) {
+            let (pulse, gap) = (pair[0], pair[1]);
+            let bit = (gap > threshold) == (self.one_gap > self.zero_gap);
+
+            let (nominal_pulse, nominal_gap) = if bit {
+                (self.one_pulse, self.one_gap)
+            } else {
+                (self.zero_pulse, self.zero_gap)
+            };
+
+            if !matches(pulse, nominal_pulse) || !matches(gap, nominal_gap) {
+                return None;
+            }
+
+            bits.push(bit);
+        }
+
+        Some(bits)
+    }
+
+    /// The gap duration `decode` uses to tell a one from a zero, halfway between both gaps.
+    pub fn bit_threshold_us(&self) -> u32 {
+        self.one_gap.min(self.zero_gap) + self.one_gap.abs_diff(self.zero_gap) / 2
+    }
+
+    /// The leading gap a receiver with automatic gain control reports, its AGC settling eating
+    /// `agc_loss_us` into the first gap.
+    pub fn expected_first_gap_with_agc(&self, agc_loss_us: u32) -> u32 {
+        self.leading_gap.saturating_sub(agc_loss_us)
+    }
+
+    /// Same as `decode`, expecting the leading gap shortened by `agc_loss_us`.
+    pub fn decode_with_agc(
+        &self,
+        timings: &[u32],
+        tolerance_us: u32,
+        agc_loss_us: u32,
+    ) -> Option<BitVec> {
+        self.with_leading(
+            self.leading_pulse,
+            self.expected_first_gap_with_agc(agc_loss_us),
+        )
+        .decode(timings, tolerance_us)
+    }
+
+    /// Same as `decode`, parsing a text dump of durations separated by whitespace or commas.
+    /// Signs are ignored, so dumps marking spaces as negative are accepted too.
+    pub fn decode_str(&self, s: &str, tolerance_us: u32) -> Option<BitVec> {
+        let timings = s
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|token| !token.is_empty())
+            .map(|token| token.trim_start_matches(['+', '-']).parse().ok())
+            .collect::<Option<Vec<u32>>>()?;
+
+        self.decode(&timings, tolerance_us)
+    }
+
+    /// Same as `decode`, also reporting the measured leading pulse and gap against nominal.
+    pub fn decode_with_report(&self, timings: &[u32], tolerance_us: u32) -> Option<DecodeReport> {
+        let bits = self.decode(timings, tolerance_us)?;
+
+        Some(DecodeReport {
+            bits,
+            leading_pulse: timings[0],
+            leading_gap: timings[1],
+            nominal_leading_pulse: self.leading_pulse,
+            nominal_leading_gap: self.leading_gap,
+        })
+    }
+
+    /// Decodes signed durations where marks are positive and spaces are negative.
+    pub fn decode_signed(&self, timings: &[i32], tolerance_us: u32) -> Option<BitVec> {
+        let alternates = timings
+            .iter()
+            .enumerate()
+            .all(|(i, duration)| (i % 2 == 0) == (*duration > 0) && *duration != 0);
+
+        if !alternates {
+            return None;
+        }
+
+        let timings: Vec<u32> = timings.iter().map(|x| x.unsigned_abs()).collect();
+
+        self.decode(&timings, tolerance_us)
+    }
+
+    /// Decodes signed durations from a receiver with inverted polarity, which reports marks as
+    /// spaces and the other way around, so the capture starts with a negative duration. Returns
+    /// `None` for a capture holding `i32::MIN`, which has no positive counterpart.
+    pub fn decode_inverted(&self, timings: &[i32], tolerance_us: u32) -> Option<BitVec> {
+        let timings: Vec<i32> = timings
+            .iter()
+            .map(|x| x.checked_neg())
+            .collect::<Option<_>>()?;
+
+        self.decode_signed(&timings, tolerance_us)
+    }
+}
+
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e3779b97f4a7c15);
+
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
+
+/// How much longer in microseconds sending `bits` takes with `a` than with `b`.
+pub fn duration_diff(a: &InfraredProtocol, b: &InfraredProtocol, bits: &BitVec) -> i64 {
+    a.duration_us(bits) as i64 - b.duration_us(bits) as i64
 }
 
 #[cfg(test)]
@@ -47,8 +533,26 @@ mod tests {
         one_gap: 1500,
         zero_pulse: 500,
         zero_gap: 500,
+        carrier_hz: 38000,
+        logger: None,
     };
 
+    #[test]
+    fn when_with_leading_then_only_leading_timings_change() {
+        let result = IR.with_leading(9000, 4000);
+
+        assert_eq!((result.leading_pulse, result.leading_gap), (9000, 4000));
+        assert_eq!(
+            (
+                result.one_pulse,
+                result.one_gap,
+                result.zero_pulse,
+                result.zero_gap
+            ),
+            (IR.one_pulse, IR.one_gap, IR.zero_pulse, IR.zero_gap)
+        );
+    }
+
     #[test]
     fn given_simple_data_then_is_encoded_properly() {
         let mut data = BitVec::from_elem(2, false);
@@ -68,4 +572,467 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn given_distinct_symbols_then_protocol_is_self_consistent() {
+        assert!(IR.self_consistent());
+    }
+
+    #[test]
+    fn given_overlapping_gaps_then_protocol_is_not_self_consistent() {
+        let ambiguous = InfraredProtocol { one_gap: 600, ..IR };
+
+        assert!(!ambiguous.self_consistent());
+    }
+
+    #[test]
+    fn when_with_carrier_hz_then_only_carrier_changes() {
+        let result = IR.with_carrier_hz(56000);
+
+        assert_eq!(result.carrier_hz(), 56000);
+        assert_eq!(
+            result.encode(BitVec::from_elem(2, true)),
+            IR.encode(BitVec::from_elem(2, true))
+        );
+    }
+
+    #[test]
+    fn given_bit_count_then_encoded_size_matches_encode_output() {
+        let data = BitVec::from_elem(48, true);
+
+        assert_eq!(InfraredProtocol::timing_len(48), IR.encode(data).len());
+        assert_eq!(InfraredProtocol::encoded_byte_size(48), 392);
+    }
+
+    #[test]
+    fn given_data_then_pronto_code_uses_carrier_cycles() {
+        let pronto = IR.to_pronto(BitVec::from_bytes(&[0b10100001])).unwrap();
+
+        assert_eq!(&pronto[..4], &[0x0000, 0x006d, 9, 0]);
+        assert_eq!(&pronto[4..8], &[171, 171, 19, 57]);
+        assert_eq!(pronto.len(), 4 + 18);
+    }
+
+    #[test]
+    fn given_zero_or_unrepresentable_carrier_then_pronto_code_is_none() {
+        let data = BitVec::from_bytes(&[0b10100001]);
+
+        assert_eq!(IR.with_carrier_hz(0).to_pronto(data.clone()), None);
+        assert_eq!(IR.with_carrier_hz(50).to_pronto(data.clone()), None);
+        assert_eq!(IR.with_carrier_hz(10_000_000).to_pronto(data), None);
+    }
+
+    #[test]
+    fn given_data_then_first_rmt_item_is_the_leading_pulse() {
+        let items = IR.to_rmt_items(BitVec::from_bytes(&[0b10100001]));
+
+        assert_eq!(items[0], (4500, true, 4500, false));
+        assert_eq!(items[1], (500, true, 1500, false));
+        assert_eq!(items.len(), 9);
+    }
+
+    #[test]
+    fn given_simple_data_then_lirc_output_alternates_pulse_and_space() {
+        let mut data = BitVec::from_elem(2, false);
+        data.set(1, true);
+
+        let result = IR.to_lirc(data);
+
+        assert_eq!(
+            result,
+            "pulse 4500\nspace 4500\npulse 500\nspace 500\npulse 500\nspace 1500\n"
+        );
+    }
+
+    #[test]
+    fn given_encoded_data_then_is_decoded_back() {
+        let data = BitVec::from_bytes(&[0b10100001, 0b01101110]);
+
+        assert_eq!(IR.decode(&IR.encode(data.clone()), 0), Some(data));
+    }
+
+    #[test]
+    fn given_timings_within_tolerance_then_are_decoded() {
+        let timings = [4600, 4400, 450, 1600, 550, 450];
+
+        let result = IR.decode(&timings, 100).unwrap();
+
+        assert_eq!(result.iter().collect::<Vec<bool>>(), vec![true, false]);
+    }
+
+    #[test]
+    fn given_trailing_stop_pulse_then_is_skipped() {
+        let data = BitVec::from_bytes(&[0b10100001]);
+        let mut timings = IR.encode(data.clone());
+        timings.push(IR.one_pulse);
+
+        assert_eq!(IR.decode(&timings, 0), Some(data));
+    }
+
+    #[test]
+    fn given_trailing_gap_duration_then_is_not_decoded() {
+        let mut timings = IR.encode(BitVec::from_bytes(&[0b10100001]));
+        timings.push(IR.one_gap);
+
+        assert_eq!(IR.decode(&timings, 100), None);
+    }
+
+    #[test]
+    fn given_timings_out_of_tolerance_then_are_not_decoded() {
+        let timings = [4500, 4500, 500, 1000];
+
+        assert_eq!(IR.decode(&timings, 100), None);
+    }
+
+    #[test]
+    fn given_signed_encoding_then_is_decoded_back() {
+        let data = BitVec::from_bytes(&[0b10100001, 0b01101110]);
+
+        let signed = IR.encode_signed(data.clone());
+
+        assert_eq!(signed[0], IR.leading_pulse as i32);
+        assert_eq!(signed[1], -(IR.leading_gap as i32));
+        assert_eq!(IR.decode_signed(&signed, 0), Some(data));
+    }
+
+    #[test]
+    fn given_signed_timings_not_alternating_then_are_not_decoded() {
+        let timings = [4500, -4500, 500, 1500];
+
+        assert_eq!(IR.decode_signed(&timings, 0), None);
+    }
+
+    #[test]
+    fn given_step_then_timings_are_rounded_to_nearest_multiple() {
+        let ir = InfraredProtocol {
+            leading_pulse: 4350,
+            leading_gap: 4351,
+            one_pulse: 550,
+            one_gap: 1549,
+            zero_pulse: 550,
+            zero_gap: 551,
+            carrier_hz: 38000,
+            logger: None,
+        };
+        let mut data = BitVec::from_elem(2, false);
+        data.set(1, true);
+
+        assert_eq!(
+            ir.encode_quantized(data.clone(), 10),
+            vec!(4350, 4350, 550, 550, 550, 1550)
+        );
+        assert_eq!(
+            ir.encode_quantized(data, 100),
+            vec!(4300, 4400, 500, 600, 500, 1500)
+        );
+    }
+
+    #[test]
+    fn given_data_then_transitions_start_at_zero_and_are_monotonic() {
+        let data = BitVec::from_bytes(&[0b10100001]);
+
+        let result = IR.to_transitions(data);
+
+        assert_eq!(result.len(), 18);
+        assert_eq!(result[0], (true, 0));
+        assert_eq!(result[1], (false, IR.leading_pulse as u64));
+        assert!(result.windows(2).all(|pair| pair[0].1 < pair[1].1));
+        assert!(result.windows(2).all(|pair| pair[0].0 != pair[1].0));
+    }
+
+    #[test]
+    fn given_timings_then_report_captures_leading_durations() {
+        let timings = [4620, 4410, 500, 1500];
+
+        let report = IR.decode_with_report(&timings, 150).unwrap();
+
+        assert_eq!(report.bits, BitVec::from_elem(1, true));
+        assert_eq!(report.leading_pulse, 4620);
+        assert_eq!(report.leading_gap, 4410);
+        assert_eq!(report.nominal_leading_pulse, IR.leading_pulse);
+        assert_eq!(report.nominal_leading_gap, IR.leading_gap);
+    }
+
+    #[test]
+    fn given_guard_then_each_bit_gap_is_increased() {
+        let mut data = BitVec::from_elem(2, false);
+        data.set(1, true);
+
+        let result = IR.encode_with_guard(data, 50);
+
+        assert_eq!(
+            result,
+            vec!(
+                IR.leading_pulse,
+                IR.leading_gap,
+                IR.zero_pulse,
+                IR.zero_gap + 50,
+                IR.one_pulse,
+                IR.one_gap + 50,
+            )
+        );
+    }
+
+    #[test]
+    fn given_data_then_arduino_array_is_generated() {
+        let mut data = BitVec::from_elem(2, false);
+        data.set(1, true);
+
+        let result = IR.to_arduino_array("power_on", data);
+
+        assert_eq!(
+            result,
+            "const uint16_t power_on[] = {4500, 4500, 500, 500, 500, 1500};\n\
+             #define POWER_ON_LENGTH 6\n"
+        );
+    }
+
+    #[test]
+    fn given_close_protocols_then_are_not_distinguishable() {
+        let close = InfraredProtocol {
+            leading_pulse: 4350,
+            leading_gap: 4350,
+            one_pulse: 550,
+            one_gap: 1550,
+            zero_pulse: 550,
+            zero_gap: 550,
+            carrier_hz: 38000,
+            logger: None,
+        };
+
+        assert!(!IR.is_distinguishable_from(&close, 150));
+    }
+
+    #[test]
+    fn given_distant_protocols_then_are_distinguishable() {
+        let distant = InfraredProtocol {
+            leading_pulse: 9000,
+            leading_gap: 4500,
+            one_pulse: 560,
+            one_gap: 1690,
+            zero_pulse: 560,
+            zero_gap: 560,
+            carrier_hz: 38000,
+            logger: None,
+        };
+
+        assert!(IR.is_distinguishable_from(&distant, 150));
+        assert!(distant.is_distinguishable_from(&IR, 150));
+    }
+
+    #[test]
+    fn given_preamble_then_alternating_bits_precede_the_frame() {
+        let data = BitVec::from_bytes(&[0b10100001]);
+
+        let result = IR.encode_with_preamble(data.clone(), 3);
+
+        assert_eq!(
+            result[..6],
+            [
+                IR.one_pulse,
+                IR.one_gap,
+                IR.zero_pulse,
+                IR.zero_gap,
+                IR.one_pulse,
+                IR.one_gap,
+            ]
+        );
+        assert_eq!(result[6..], IR.encode(data)[..]);
+    }
+
+    #[test]
+    fn given_inverted_polarity_capture_then_is_decoded() {
+        let data = BitVec::from_bytes(&[0b10100001, 0b01101110]);
+        let capture: Vec<i32> = IR.encode_signed(data.clone()).iter().map(|x| -x).collect();
+
+        assert_eq!(IR.decode_signed(&capture, 0), None);
+        assert_eq!(IR.decode_inverted(&capture, 0), Some(data));
+    }
+
+    #[test]
+    fn given_inverted_capture_with_minimum_duration_then_is_not_decoded() {
+        assert_eq!(IR.decode_inverted(&[i32::MIN, 4500], 0), None);
+    }
+
+    #[test]
+    fn given_logger_then_is_called_with_encoded_frame() {
+        use std::cell::RefCell;
+
+        thread_local! {
+            static LOGGED: RefCell<Option<(BitVec, Vec<u32>)>> = const { RefCell::new(None) };
+        }
+
+        fn logger(bits: &BitVec, timings: &[u32]) {
+            LOGGED.with(|logged| *logged.borrow_mut() = Some((bits.clone(), timings.to_vec())));
+        }
+
+        let ir = InfraredProtocol {
+            logger: Some(logger),
+            ..IR
+        };
+        let data = BitVec::from_bytes(&[0b10100001]);
+
+        let result = ir.encode(data.clone());
+
+        LOGGED.with(|logged| assert_eq!(*logged.borrow(), Some((data, result))));
+    }
+
+    #[test]
+    fn given_frame_capture_then_looks_valid() {
+        let mut timings = IR.encode(BitVec::from_bytes(&[0b10100001]));
+
+        assert!(IR.looks_valid(&timings));
+
+        timings.push(IR.one_pulse);
+        assert!(IR.looks_valid(&timings));
+    }
+
+    #[test]
+    fn given_noise_then_does_not_look_valid() {
+        assert!(!IR.looks_valid(&[]));
+        assert!(!IR.looks_valid(&[120, 9800, 33, 2710, 640, 12]));
+        assert!(!IR.looks_valid(&[4500, 4500, 500, 1500, 2000, 500]));
+    }
+
+    #[test]
+    fn given_data_then_duration_is_sum_of_timings() {
+        let mut data = BitVec::from_elem(2, false);
+        data.set(1, true);
+
+        assert_eq!(IR.duration_us(&data), 4500 + 4500 + 500 + 500 + 500 + 1500);
+    }
+
+    #[test]
+    fn given_faster_protocol_then_duration_diff_is_positive() {
+        let faster = InfraredProtocol {
+            leading_pulse: 2000,
+            leading_gap: 1000,
+            one_pulse: 300,
+            one_gap: 900,
+            zero_pulse: 300,
+            zero_gap: 300,
+            carrier_hz: 38000,
+            logger: None,
+        };
+        let data = BitVec::from_bytes(&[0b10100001]);
+
+        let diff = duration_diff(&IR, &faster, &data);
+
+        assert_eq!(
+            diff,
+            IR.duration_us(&data) as i64 - faster.duration_us(&data) as i64
+        );
+        assert_eq!(duration_diff(&faster, &IR, &data), -diff);
+        assert!(diff > 0);
+    }
+
+    #[test]
+    fn when_ping_frame_then_contains_only_lead_and_stop_pulse() {
+        let result = IR.ping_frame();
+
+        assert_eq!(
+            result,
+            vec!(IR.leading_pulse, IR.leading_gap, IR.zero_pulse)
+        );
+        assert_eq!(IR.decode(&result, 0), Some(BitVec::new()));
+    }
+
+    #[test]
+    fn given_seed_then_jittered_gaps_stay_in_range_and_are_reproducible() {
+        let data = BitVec::from_bytes(&[0b10100001]);
+        let frame = IR.encode(data.clone());
+
+        let result = IR.encode_repeated_jittered(data.clone(), 4, 20000, 500, 42);
+
+        assert_eq!(result.len(), frame.len() * 4);
+        for i in 1..4 {
+            let boundary = i * frame.len() - 1;
+            let gap = result[boundary] - frame[frame.len() - 1];
+
+            assert!((19500..=20500).contains(&gap));
+            assert_eq!(
+                result[boundary + 1..boundary + 1 + frame.len() - 1],
+                frame[..frame.len() - 1]
+            );
+        }
+        assert_eq!(result, IR.encode_repeated_jittered(data, 4, 20000, 500, 42));
+    }
+
+    #[test]
+    fn given_text_dump_then_decode_str_matches_decode() {
+        let data = BitVec::from_bytes(&[0b10100001]);
+        let dump =
+            "+4500 -4500, +500 -1500 +500 -500\n+500 -1500 500 500 500 500 500 500 500 500 500 1500 500";
+
+        assert_eq!(IR.decode_str(dump, 100), Some(data));
+        assert_eq!(IR.decode_str("4500 4500 abc", 100), None);
+    }
+
+    #[test]
+    fn given_agc_shortened_first_gap_then_decode_with_agc_succeeds() {
+        let data = BitVec::from_bytes(&[0b10100001]);
+        let mut timings = IR.encode(data.clone());
+        timings[1] -= 800;
+
+        assert_eq!(IR.expected_first_gap_with_agc(800), 3700);
+        assert_eq!(IR.decode(&timings, 100), None);
+        assert_eq!(IR.decode_with_agc(&timings, 100, 800), Some(data));
+    }
+
+    #[test]
+    fn given_byte_spacing_then_extra_gap_follows_each_inner_byte_boundary() {
+        let data = BitVec::from_bytes(&[0b10100001, 0b01101110, 0b11111111]);
+        let plain = IR.encode(data.clone());
+
+        let spaced = IR.encode_byte_spaced(data, 3000);
+
+        let lengthened: Vec<usize> = (0..plain.len())
+            .filter(|i| spaced[*i] != plain[*i])
+            .collect();
+
+        assert_eq!(lengthened, vec![17, 33]);
+        assert_eq!(spaced[17], plain[17] + 3000);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn given_elios_timings_in_toml_then_protocol_matches_elios_ir() {
+        use crate::elios::ELIOS_IR;
+
+        let definition = "# Elios\n\
+                          leading_pulse = 4350\n\
+                          leading_gap = 4_350\n\
+                          one_pulse = 550\n\
+                          one_gap = 1550 # a one\n\
+                          zero_pulse = 550\n\
+                          zero_gap = 550\n\
+                          carrier_hz = 38000\n";
+        let fields = |ir: InfraredProtocol| {
+            (
+                ir.leading_pulse,
+                ir.leading_gap,
+                ir.one_pulse,
+                ir.one_gap,
+                ir.zero_pulse,
+                ir.zero_gap,
+                ir.carrier_hz,
+            )
+        };
+
+        let protocol = InfraredProtocol::from_toml(definition).unwrap();
+
+        assert_eq!(fields(protocol), fields(ELIOS_IR));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn given_incomplete_or_duplicated_toml_then_protocol_is_rejected() {
+        assert!(InfraredProtocol::from_toml("leading_pulse = 4350").is_none());
+        assert!(InfraredProtocol::from_toml(
+            "leading_pulse = 4350\nleading_pulse = 9000\nleading_gap = 4350\n\
+             one_pulse = 550\none_gap = 1550\nzero_pulse = 550\nzero_gap = 550\n\
+             carrier_hz = 38000"
+        )
+        .is_none());
+    }
 }
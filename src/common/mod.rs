@@ -1,7 +1,13 @@
+mod climate;
+mod frame;
 mod infrared;
+mod registry;
 mod temperature;
 mod utils;
 
-pub use infrared::InfraredProtocol;
-pub use temperature::Temperature;
-pub use utils::{bitreverse, AsBitVec};
+pub use climate::ClimateState;
+pub use frame::Frame;
+pub use infrared::{duration_diff, DecodeReport, InfraredProtocol};
+pub use registry::guess_protocol;
+pub use temperature::{DisplayTemperature, Temperature, TemperaturePreset, TemperatureUnit};
+pub use utils::{bitreverse, crc8, AsBitVec};
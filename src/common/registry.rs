@@ -0,0 +1,38 @@
+use crate::common::InfraredProtocol;
+use crate::elios::ELIOS_IR;
+
+/// Every supported protocol with the name it is reported under.
+const PROTOCOLS: [(&str, InfraredProtocol); 1] = [("elios", ELIOS_IR)];
+
+/// Names the protocol a capture most likely comes from: among the registered protocols that
+/// decode it within `tolerance_us`, the one whose leading pulse and gap are closest.
+pub fn guess_protocol(timings: &[u32], tolerance_us: u32) -> Option<&'static str> {
+    PROTOCOLS
+        .iter()
+        .filter(|(_, protocol)| protocol.decode(timings, tolerance_us).is_some())
+        .min_by_key(|(_, protocol)| {
+            timings[0].abs_diff(protocol.leading_pulse) + timings[1].abs_diff(protocol.leading_gap)
+        })
+        .map(|(name, _)| *name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{AsBitVec, Temperature};
+    use crate::elios::EliosState;
+
+    #[test]
+    fn given_elios_capture_then_protocol_is_guessed() {
+        let timings = ELIOS_IR.encode(EliosState::cool(Temperature::Celcius(22)).as_bitvec());
+
+        assert_eq!(guess_protocol(&timings, 200), Some("elios"));
+    }
+
+    #[test]
+    fn given_unknown_capture_then_no_protocol_is_guessed() {
+        let timings = [9000, 4500, 560, 1690, 560, 560];
+
+        assert_eq!(guess_protocol(&timings, 200), None);
+    }
+}
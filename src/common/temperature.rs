@@ -1,21 +1,205 @@
-#[derive(Debug, Copy, Clone, PartialEq)]
+use crate::elios::EliosState;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum Temperature {
     Celcius(u8),
     Fahrenheit(u8),
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum TemperatureUnit {
+    Celcius,
+    Fahrenheit,
+}
+
+/// Named setpoints for quick-select UIs. `Custom` overrides a preset with any setpoint.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum TemperaturePreset {
+    /// 22°C, noticeably cool
+    Cool,
+    /// 24°C, the usual comfortable setting
+    Comfort,
+    /// 26°C, a little warmer to sleep
+    Sleep,
+    /// 28°C, cooling only as much as needed to save energy
+    Eco,
+    Custom(Temperature),
+}
+
+impl TemperaturePreset {
+    pub fn to_temperature(self) -> Temperature {
+        match self {
+            TemperaturePreset::Cool => Temperature::Celcius(22),
+            TemperaturePreset::Comfort => Temperature::Celcius(24),
+            TemperaturePreset::Sleep => Temperature::Celcius(26),
+            TemperaturePreset::Eco => Temperature::Celcius(28),
+            TemperaturePreset::Custom(temperature) => temperature,
+        }
+    }
+}
+
+/// A temperature shown in a chosen unit while the original value is kept untouched, so that
+/// converting back and forth for display never drifts the stored setpoint.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct DisplayTemperature {
+    stored: Temperature,
+    unit: TemperatureUnit,
+}
+
+impl DisplayTemperature {
+    pub fn stored(&self) -> Temperature {
+        self.stored
+    }
+
+    pub fn display(&self) -> Temperature {
+        match self.unit {
+            TemperatureUnit::Celcius => self.stored.as_celcius(),
+            TemperatureUnit::Fahrenheit => self.stored.as_fahrenheit(),
+        }
+    }
+}
+
 impl Temperature {
+    pub fn unit(&self) -> TemperatureUnit {
+        match self {
+            Temperature::Celcius(_) => TemperatureUnit::Celcius,
+            Temperature::Fahrenheit(_) => TemperatureUnit::Fahrenheit,
+        }
+    }
+
+    /// Rounds `value` to the nearest degree and clamps it to the range the unit accepts in
+    /// `unit`, so the result is always encodable as is. `NaN` snaps to the minimum.
+    pub fn snap(value: f32, unit: TemperatureUnit) -> Temperature {
+        let range = EliosState::temperature_range(unit);
+        let temp = (value.round() as u8).clamp(*range.start(), *range.end());
+
+        match unit {
+            TemperatureUnit::Celcius => Temperature::Celcius(temp),
+            TemperatureUnit::Fahrenheit => Temperature::Fahrenheit(temp),
+        }
+    }
+
+    pub fn with_display_unit(self, unit: TemperatureUnit) -> DisplayTemperature {
+        DisplayTemperature { stored: self, unit }
+    }
+
+    /// The absolute temperature in millikelvin, rounded to the nearest for Fahrenheit values.
+    pub fn as_millikelvin(&self) -> u32 {
+        match self {
+            Temperature::Celcius(temp) => *temp as u32 * 1000 + 273_150,
+            Temperature::Fahrenheit(temp) => ((*temp as u32 * 1000 + 459_670) * 5 + 4) / 9,
+        }
+    }
+
+    /// Converts to Fahrenheit, rounded to the nearest degree.
     pub fn as_fahrenheit(&self) -> Self {
         match self {
-            Temperature::Celcius(temp) => Temperature::Fahrenheit(temp * 9 / 5 + 32),
-            Temperature::Fahrenheit(_) => self.clone(),
+            Temperature::Celcius(temp) => {
+                let fahrenheit = (*temp as u16 * 18 + 5) / 10 + 32;
+
+                Temperature::Fahrenheit(fahrenheit.min(u8::MAX as u16) as u8)
+            }
+            Temperature::Fahrenheit(_) => *self,
         }
     }
 
+    /// Converts to Celcius, rounded to the nearest degree.
     pub fn as_celcius(&self) -> Self {
         match self {
-            Temperature::Celcius(_) => self.clone(),
-            Temperature::Fahrenheit(temp) => Temperature::Celcius((temp - 32) * 5 / 9),
+            Temperature::Celcius(_) => *self,
+            Temperature::Fahrenheit(temp) => {
+                Temperature::Celcius(((temp.saturating_sub(32) as u16 * 10 + 9) / 18) as u8)
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn given_display_unit_then_stored_value_does_not_drift() {
+        let temperature = Temperature::Celcius(22).with_display_unit(TemperatureUnit::Fahrenheit);
+
+        assert_eq!(temperature.display(), Temperature::Fahrenheit(72));
+        assert_eq!(temperature.stored(), Temperature::Celcius(22));
+        assert_eq!(
+            temperature
+                .stored()
+                .with_display_unit(TemperatureUnit::Celcius)
+                .display(),
+            Temperature::Celcius(22)
+        );
+    }
+
+    #[test]
+    fn given_high_celcius_then_fahrenheit_conversion_does_not_overflow() {
+        assert_eq!(
+            Temperature::Celcius(30).as_fahrenheit(),
+            Temperature::Fahrenheit(86)
+        );
+    }
+
+    #[test]
+    fn given_freezing_fahrenheit_then_celcius_conversion_does_not_underflow() {
+        assert_eq!(
+            Temperature::Fahrenheit(20).as_celcius(),
+            Temperature::Celcius(0)
+        );
+    }
+
+    #[test]
+    fn given_temperatures_then_millikelvin_are_absolute() {
+        assert_eq!(Temperature::Celcius(0).as_millikelvin(), 273_150);
+        assert_eq!(Temperature::Celcius(22).as_millikelvin(), 295_150);
+        assert_eq!(Temperature::Fahrenheit(32).as_millikelvin(), 273_150);
+        assert_eq!(Temperature::Fahrenheit(212).as_millikelvin(), 373_150);
+        assert_eq!(Temperature::Fahrenheit(62).as_millikelvin(), 289_817);
+    }
+
+    #[test]
+    fn given_float_then_snapped_to_nearest_encodable_degree() {
+        assert_eq!(
+            Temperature::snap(22.6, TemperatureUnit::Celcius),
+            Temperature::Celcius(23)
+        );
+        assert_eq!(
+            Temperature::snap(99.0, TemperatureUnit::Celcius),
+            Temperature::Celcius(30)
+        );
+        assert_eq!(
+            Temperature::snap(-5.0, TemperatureUnit::Fahrenheit),
+            Temperature::Fahrenheit(62)
+        );
+    }
+
+    #[test]
+    fn given_nan_then_snapped_to_minimum() {
+        assert_eq!(
+            Temperature::snap(f32::NAN, TemperatureUnit::Celcius),
+            Temperature::Celcius(17)
+        );
+    }
+
+    #[test]
+    fn given_presets_then_each_maps_to_its_celcius_setpoint() {
+        let presets = [
+            (TemperaturePreset::Cool, 22),
+            (TemperaturePreset::Comfort, 24),
+            (TemperaturePreset::Sleep, 26),
+            (TemperaturePreset::Eco, 28),
+        ];
+
+        for (preset, temp) in presets.iter() {
+            assert_eq!(preset.to_temperature(), Temperature::Celcius(*temp));
+        }
+    }
+
+    #[test]
+    fn given_custom_preset_then_its_setpoint_is_used() {
+        let preset = TemperaturePreset::Custom(Temperature::Fahrenheit(75));
+
+        assert_eq!(preset.to_temperature(), Temperature::Fahrenheit(75));
+    }
+}
@@ -1,13 +1,36 @@
 use bit_vec::BitVec;
 
 pub trait AsBitVec {
+    #[allow(clippy::wrong_self_convention)]
     fn as_bitvec(self) -> BitVec;
+
+    /// The whole frame in reverse bit order, for variants sending it LSB-first end to end.
+    #[allow(clippy::wrong_self_convention)]
+    fn as_bitvec_reversed(self) -> BitVec
+    where
+        Self: Sized,
+    {
+        self.as_bitvec().iter().rev().collect()
+    }
 }
 
 pub fn bitreverse(x: &u8) -> u8 {
     (0..8_u8).fold(0, |acc, i| acc | (x >> i & 1) << (7 - i))
 }
 
+/// CRC-8 with polynomial 0x07, no reflection and a zero initial value (CRC-8/SMBUS).
+pub fn crc8(data: &[u8]) -> u8 {
+    data.iter().fold(0, |crc, byte| {
+        (0..8).fold(crc ^ byte, |crc, _| {
+            if crc & 0x80 != 0 {
+                crc << 1 ^ 0x07
+            } else {
+                crc << 1
+            }
+        })
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -19,4 +42,27 @@ mod tests {
 
         assert_eq!(bitreverse(&data), expected);
     }
+
+    #[test]
+    fn given_check_string_then_crc8_matches_standard_value() {
+        assert_eq!(crc8(b"123456789"), 0xf4);
+        assert_eq!(crc8(&[]), 0);
+    }
+
+    #[test]
+    fn when_bitvec_reversed_then_is_mirror_of_bitvec() {
+        struct Bits(u8);
+
+        impl AsBitVec for Bits {
+            fn as_bitvec(self) -> BitVec {
+                BitVec::from_bytes(&[self.0, 0])
+            }
+        }
+
+        let normal = Bits(0b10010111).as_bitvec();
+        let reversed = Bits(0b10010111).as_bitvec_reversed();
+
+        assert_eq!(reversed, BitVec::from_bytes(&[0, 0b11101001]));
+        assert!((0..normal.len()).all(|i| normal[i] == reversed[normal.len() - 1 - i]));
+    }
 }
@@ -0,0 +1,229 @@
+use crate::common::Temperature;
+use crate::elios::{EliosFanSpeed as FanSpeed, EliosMode as Mode, EliosState};
+
+/// A setting that was changed from what was requested while building a state.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Adjustment {
+    TemperatureClamped {
+        requested: Temperature,
+        applied: Temperature,
+    },
+    SleepDropped,
+    FanSpeedForced {
+        requested: FanSpeed,
+        applied: FanSpeed,
+    },
+}
+
+/// Every normalization applied by `EliosStateBuilder::build_with_report`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BuildReport {
+    pub adjustments: Vec<Adjustment>,
+}
+
+/// Builds an `EliosState` from the settings that were given, defaulting to a powered unit
+/// without sleep. Unlike `new`, a fan speed requested in a mode where the unit manages the fan is
+/// forced to off rather than rejected, and reported as such.
+#[derive(Debug, Copy, Clone)]
+pub struct EliosStateBuilder {
+    mode: Mode,
+    fan_speed: Option<FanSpeed>,
+    temperature: Option<Temperature>,
+    clamped_from: Option<Temperature>,
+    powered: bool,
+    sleep: bool,
+}
+
+impl EliosStateBuilder {
+    pub fn new(mode: Mode) -> Self {
+        Self {
+            mode,
+            fan_speed: None,
+            temperature: None,
+            clamped_from: None,
+            powered: true,
+            sleep: false,
+        }
+    }
+
+    pub fn fan_speed(mut self, fan_speed: FanSpeed) -> Self {
+        self.fan_speed = Some(fan_speed);
+        self
+    }
+
+    pub fn temperature(mut self, temperature: Temperature) -> Self {
+        self.temperature = Some(temperature);
+        self.clamped_from = None;
+        self
+    }
+
+    /// Same as `temperature`, clamping to the supported range right away. The clamping still
+    /// shows in the `BuildReport` against the original value.
+    pub fn temperature_clamped(mut self, temperature: Temperature) -> Self {
+        let range = EliosState::temperature_range(temperature.unit());
+        let clamped = match temperature {
+            Temperature::Celcius(temp) => {
+                Temperature::Celcius(temp.clamp(*range.start(), *range.end()))
+            }
+            Temperature::Fahrenheit(temp) => {
+                Temperature::Fahrenheit(temp.clamp(*range.start(), *range.end()))
+            }
+        };
+
+        self.temperature = Some(clamped);
+        self.clamped_from = Some(temperature).filter(|requested| *requested != clamped);
+        self
+    }
+
+    pub fn powered(mut self, powered: bool) -> Self {
+        self.powered = powered;
+        self
+    }
+
+    pub fn sleep(mut self, sleep: bool) -> Self {
+        self.sleep = sleep;
+        self
+    }
+
+    /// Returns `None` for the combinations `new` rejects, such as a temperature in fan mode.
+    pub fn build(self) -> Option<EliosState> {
+        self.build_with_report().map(|(state, _)| state)
+    }
+
+    /// Same as `build`, also listing every setting that was clamped, dropped or forced.
+    pub fn build_with_report(self) -> Option<(EliosState, BuildReport)> {
+        let (state, overrides) = EliosState::new_lenient(
+            self.fan_speed,
+            self.mode,
+            self.temperature,
+            self.powered,
+            self.sleep,
+        )?;
+
+        let mut report = BuildReport::default();
+
+        if let (Some(requested), Some(applied)) = (self.temperature, state.temperature()) {
+            let requested = self.clamped_from.unwrap_or(requested);
+
+            if requested != applied {
+                report
+                    .adjustments
+                    .push(Adjustment::TemperatureClamped { requested, applied });
+            }
+        }
+
+        if overrides.sleep {
+            report.adjustments.push(Adjustment::SleepDropped);
+        }
+
+        if let (true, Some(requested)) = (overrides.fan_speed, self.fan_speed) {
+            report.adjustments.push(Adjustment::FanSpeedForced {
+                requested,
+                applied: state.fan_speed(),
+            });
+        }
+
+        Some((state, report))
+    }
+}
+
+impl EliosState {
+    pub fn builder(mode: Mode) -> EliosStateBuilder {
+        EliosStateBuilder::new(mode)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn given_plain_settings_then_builder_matches_constructor() {
+        let state = EliosState::builder(Mode::Cold)
+            .fan_speed(FanSpeed::Low)
+            .temperature(Temperature::Celcius(22))
+            .build();
+
+        assert_eq!(
+            state,
+            EliosState::new(
+                Some(FanSpeed::Low),
+                Mode::Cold,
+                Some(Temperature::Celcius(22)),
+                true,
+                false,
+            )
+        );
+    }
+
+    #[test]
+    fn given_dry_mode_with_sleep_then_report_lists_every_adjustment() {
+        let (state, report) = EliosState::builder(Mode::Dry)
+            .fan_speed(FanSpeed::High)
+            .temperature(Temperature::Celcius(12))
+            .sleep(true)
+            .build_with_report()
+            .unwrap();
+
+        assert!(!state.sleep());
+        assert_eq!(
+            report.adjustments,
+            vec![
+                Adjustment::TemperatureClamped {
+                    requested: Temperature::Celcius(12),
+                    applied: Temperature::Celcius(17),
+                },
+                Adjustment::SleepDropped,
+                Adjustment::FanSpeedForced {
+                    requested: FanSpeed::High,
+                    applied: FanSpeed::Off,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn given_valid_settings_then_report_is_empty() {
+        let (_, report) = EliosState::builder(Mode::Heat)
+            .temperature(Temperature::Fahrenheit(70))
+            .sleep(true)
+            .build_with_report()
+            .unwrap();
+
+        assert_eq!(report, BuildReport::default());
+    }
+
+    #[test]
+    fn given_clamped_temperature_then_report_shows_original_value() {
+        let (state, report) = EliosState::builder(Mode::Cold)
+            .temperature_clamped(Temperature::Celcius(35))
+            .build_with_report()
+            .unwrap();
+
+        assert_eq!(state.temperature(), Some(Temperature::Celcius(30)));
+        assert_eq!(
+            report.adjustments,
+            vec![Adjustment::TemperatureClamped {
+                requested: Temperature::Celcius(35),
+                applied: Temperature::Celcius(30),
+            }]
+        );
+    }
+
+    #[test]
+    fn given_fan_mode_with_temperature_then_build_is_rejected() {
+        let state = EliosState::builder(Mode::Fan)
+            .temperature(Temperature::Celcius(22))
+            .build();
+
+        assert_eq!(state, None);
+    }
+
+    #[test]
+    fn given_fan_mode_without_temperature_then_sentinel_is_encoded() {
+        let state = EliosState::builder(Mode::Fan).build().unwrap();
+
+        assert_eq!(state.temperature(), None);
+        assert_eq!(state.as_value(), EliosState::fan_only().as_value());
+    }
+}
@@ -0,0 +1,190 @@
+use crate::elios::state::{step_temperature, DEFAULT_TEMPERATURE};
+use crate::elios::{EliosFanSpeed as FanSpeed, EliosMode as Mode, EliosState};
+
+/// The buttons of the physical remote. The remote's swing button is not listed since the frame
+/// has no known field for it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum RemoteButton {
+    Power,
+    ModeUp,
+    TempUp,
+    TempDown,
+    FanCycle,
+    Sleep,
+}
+
+impl RemoteButton {
+    pub const ALL: [Self; 6] = [
+        RemoteButton::Power,
+        RemoteButton::ModeUp,
+        RemoteButton::TempUp,
+        RemoteButton::TempDown,
+        RemoteButton::FanCycle,
+        RemoteButton::Sleep,
+    ];
+}
+
+impl EliosState {
+    /// The state the remote sends after `button` is pressed while showing this state.
+    pub fn press(self, button: RemoteButton) -> EliosState {
+        let mut fan_speed = Some(self.fan_speed());
+        let mut mode = self.mode();
+        let mut temperature = self.temperature();
+        let mut powered = self.powered();
+        let mut sleep = self.sleep();
+
+        match button {
+            RemoteButton::Power => powered = !powered,
+            RemoteButton::ModeUp => {
+                mode = Mode::ALL[(Mode::ALL.iter().position(|m| *m == mode).unwrap() + 1) % 5];
+                temperature = match mode {
+                    Mode::Fan => None,
+                    _ => temperature.or(Some(DEFAULT_TEMPERATURE)),
+                };
+                fan_speed = match (mode, self.fan_speed()) {
+                    (Mode::Automatic, _) | (Mode::Dry, _) | (_, FanSpeed::Off) => None,
+                    (_, fan_speed) => Some(fan_speed),
+                };
+            }
+            RemoteButton::TempUp => temperature = temperature.map(|temp| step_temperature(temp, 1)),
+            RemoteButton::TempDown => {
+                temperature = temperature.map(|temp| step_temperature(temp, -1))
+            }
+            RemoteButton::FanCycle => {
+                fan_speed = match (mode, self.fan_speed()) {
+                    (Mode::Automatic, _) | (Mode::Dry, _) => fan_speed,
+                    (_, FanSpeed::Automatic) => Some(FanSpeed::Low),
+                    (_, FanSpeed::Low) => Some(FanSpeed::Medium),
+                    (_, FanSpeed::Medium) => Some(FanSpeed::High),
+                    (_, FanSpeed::High) | (_, FanSpeed::Off) => Some(FanSpeed::Automatic),
+                }
+            }
+            RemoteButton::Sleep => sleep = !sleep,
+        }
+
+        Self::new(fan_speed, mode, temperature, powered, sleep).unwrap_or(self)
+    }
+
+    /// The frames sent by pressing temperature up `steps` times from this state, the last ones
+    /// repeating the maximum once it is reached.
+    pub fn temperature_up_sequence(self, steps: usize) -> Vec<u64> {
+        let mut state = self;
+
+        (0..steps)
+            .map(|_| {
+                state = state.press(RemoteButton::TempUp);
+                state.as_value()
+            })
+            .collect()
+    }
+}
+
+/// The frame each button sends from the default state, powered cooling at the default
+/// temperature with automatic fan, as a lookup table for replacement remotes.
+pub fn keymap() -> Vec<(RemoteButton, u64)> {
+    let state = EliosState::cool(DEFAULT_TEMPERATURE);
+
+    RemoteButton::ALL
+        .iter()
+        .map(|button| (*button, state.press(*button).as_value()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::Temperature;
+
+    #[test]
+    fn when_temp_up_pressed_then_temperature_increases_until_max() {
+        let state = EliosState::cool(Temperature::Celcius(29));
+
+        let once = state.press(RemoteButton::TempUp);
+        let twice = once.press(RemoteButton::TempUp);
+
+        assert_eq!(once, EliosState::cool(Temperature::Celcius(30)));
+        assert_eq!(twice, once);
+    }
+
+    #[test]
+    fn when_fan_cycle_pressed_then_fan_speeds_cycle() {
+        let mut state = EliosState::cool(Temperature::Celcius(22));
+        let mut speeds = Vec::new();
+
+        for _ in 0..4 {
+            state = state.press(RemoteButton::FanCycle);
+            speeds.push(state.fan_speed());
+        }
+
+        assert_eq!(
+            speeds,
+            vec![
+                FanSpeed::Low,
+                FanSpeed::Medium,
+                FanSpeed::High,
+                FanSpeed::Automatic
+            ]
+        );
+    }
+
+    #[test]
+    fn given_auto_mode_when_fan_cycle_pressed_then_nothing_changes() {
+        let state = EliosState::auto(Temperature::Celcius(22));
+
+        assert_eq!(state.press(RemoteButton::FanCycle), state);
+    }
+
+    #[test]
+    fn when_mode_up_pressed_then_every_mode_is_visited() {
+        let mut state = EliosState::cool(Temperature::Celcius(22));
+        let mut modes = Vec::new();
+
+        for _ in 0..5 {
+            state = state.press(RemoteButton::ModeUp);
+            modes.push(state.mode());
+        }
+
+        assert_eq!(
+            modes,
+            vec![
+                Mode::Dry,
+                Mode::Automatic,
+                Mode::Heat,
+                Mode::Fan,
+                Mode::Cold
+            ]
+        );
+        assert_eq!(state.temperature(), Some(DEFAULT_TEMPERATURE));
+    }
+
+    #[test]
+    fn when_power_pressed_then_power_toggles() {
+        let state = EliosState::cool(Temperature::Celcius(22));
+
+        assert!(!state.press(RemoteButton::Power).powered());
+    }
+
+    #[test]
+    fn when_temperature_stepped_up_past_max_then_sequence_is_clamped() {
+        let frames = EliosState::cool(Temperature::Celcius(28)).temperature_up_sequence(5);
+
+        let expected: Vec<u64> = [29, 30, 30, 30, 30]
+            .iter()
+            .map(|temp| EliosState::cool(Temperature::Celcius(*temp)).as_value())
+            .collect();
+
+        assert_eq!(frames, expected);
+    }
+
+    #[test]
+    fn given_keymap_then_power_sends_the_off_state() {
+        let off = EliosState::new(None, Mode::Cold, Some(DEFAULT_TEMPERATURE), false, false)
+            .unwrap()
+            .as_value();
+
+        let keymap = keymap();
+
+        assert_eq!(keymap.len(), RemoteButton::ALL.len());
+        assert!(keymap.contains(&(RemoteButton::Power, off)));
+    }
+}
@@ -0,0 +1,162 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::common::ClimateState;
+use crate::elios::EliosState;
+
+/// Tallies how many times each distinct command appears in a capture. Frames that fail to
+/// decode are counted under `None`.
+pub fn histogram(frames: &[u64]) -> HashMap<Option<EliosState>, usize> {
+    let mut counts = HashMap::new();
+
+    for frame in frames {
+        *counts.entry(EliosState::from_value(*frame)).or_insert(0) += 1;
+    }
+
+    counts
+}
+
+/// Collapses runs of identical frames, as remotes send each command several times, keeping the
+/// order of the logical commands.
+pub fn dedupe_frames(frames: &[u64]) -> Vec<u64> {
+    let mut frames = frames.to_vec();
+    frames.dedup();
+
+    frames
+}
+
+/// The distinct values each reserved bit takes across a capture, keyed by frame bit position
+/// (0 being the first bit sent). Frames are not validated, so corrupt captures show up too.
+pub fn reserved_bit_values(frames: &[u64]) -> HashMap<usize, HashSet<u8>> {
+    let mut values = HashMap::new();
+    let reserved = EliosState::field_layout()
+        .iter()
+        .filter(|(name, _)| *name == "Reserved")
+        .flat_map(|(_, bits)| bits.clone());
+
+    for position in reserved {
+        let seen = frames
+            .iter()
+            .map(|frame| (frame >> (EliosState::FRAME_BITS - 1 - position) & 1) as u8)
+            .collect();
+
+        values.insert(position, seen);
+    }
+
+    values
+}
+
+/// How many of the 48 frame bits differ between `a` and `b`, higher bits being ignored.
+pub fn frame_hamming_distance(a: u64, b: u64) -> u32 {
+    let mask = (1 << EliosState::FRAME_BITS) - 1;
+
+    ((a ^ b) & mask).count_ones()
+}
+
+impl EliosState {
+    /// Best guess for a frame that fails to decode: the valid state whose data bytes are the
+    /// fewest bit flips away from those of `value`, the checksum being ignored. Ties go to the
+    /// first state in `all_valid` order.
+    pub fn nearest_valid(value: u64) -> Option<EliosState> {
+        EliosState::all_valid()
+            .min_by_key(|state| frame_hamming_distance(state.as_value() >> 8, value >> 8))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::Temperature;
+    use crate::elios::{DecodedFrame, EliosFanSpeed, EliosMode};
+
+    #[test]
+    fn given_captured_frames_then_each_command_is_counted() {
+        let cold = EliosState::new(
+            Some(EliosFanSpeed::Automatic),
+            EliosMode::Cold,
+            Some(Temperature::Celcius(17)),
+            true,
+            false,
+        )
+        .unwrap();
+        let fan =
+            EliosState::new(Some(EliosFanSpeed::Low), EliosMode::Fan, None, true, false).unwrap();
+
+        let frames = [
+            cold.as_value(),
+            fan.as_value(),
+            cold.as_value(),
+            0xdeadbeef,
+            cold.as_value_with_checksum(0),
+        ];
+
+        let result = histogram(&frames);
+
+        assert_eq!(result.len(), 3);
+        assert_eq!(result[&Some(cold)], 2);
+        assert_eq!(result[&Some(fan)], 1);
+        assert_eq!(result[&None], 2);
+    }
+
+    #[test]
+    fn given_repeated_frames_then_consecutive_duplicates_are_collapsed() {
+        let frames = [1, 1, 1, 2, 2, 2, 1, 3, 3, 3];
+
+        assert_eq!(dedupe_frames(&frames), vec![1, 2, 1, 3]);
+    }
+
+    #[test]
+    fn given_frames_with_varying_reserved_bits_then_each_value_is_reported() {
+        let value = EliosState::cool(Temperature::Celcius(22)).as_value();
+        let flipped = value ^ 1 << (EliosState::FRAME_BITS - 1 - 17);
+
+        let result = reserved_bit_values(&[value, flipped, value]);
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[&16], [0].iter().copied().collect());
+        assert_eq!(result[&17], [0, 1].iter().copied().collect());
+    }
+
+    #[test]
+    fn given_on_and_off_frames_then_hamming_distance_counts_power_and_checksum_bits() {
+        let on = EliosState::cool(Temperature::Celcius(22));
+        let off = EliosState::new(
+            None,
+            EliosMode::Cold,
+            Some(Temperature::Celcius(22)),
+            false,
+            false,
+        )
+        .unwrap();
+
+        let checksum_bits = (on.as_bytes()[5] ^ off.as_bytes()[5]).count_ones();
+
+        assert_eq!(
+            frame_hamming_distance(on.as_value(), off.as_value()),
+            1 + checksum_bits
+        );
+        assert_eq!(
+            frame_hamming_distance(on.as_value(), on.as_value() | 1 << 48),
+            0
+        );
+    }
+
+    #[test]
+    fn given_frame_with_one_corrupted_bit_then_nearest_valid_recovers_it() {
+        let state = EliosState::heat(Temperature::Celcius(25));
+        let corrupted = state.as_value() ^ 1 << 20;
+
+        assert_eq!(EliosState::from_value(corrupted), None);
+        assert_eq!(EliosState::nearest_valid(corrupted), Some(state));
+    }
+
+    #[test]
+    fn given_checksummed_temperature_byte_with_one_corrupted_bit_then_nearest_valid_recovers_it() {
+        let state = EliosState::heat(Temperature::Celcius(25));
+        // the high reserved bit of the temperature byte (`data[2]`)
+        let corrupted = state.as_value() ^ 1 << 31;
+
+        assert!(!DecodedFrame::from_value(corrupted).checksum_valid);
+        assert_eq!(EliosState::from_value(corrupted), None);
+        assert_eq!(EliosState::nearest_valid(corrupted), Some(state));
+    }
+}
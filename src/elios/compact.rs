@@ -0,0 +1,76 @@
+use crate::common::Temperature;
+use crate::elios::{EliosFanSpeed as FanSpeed, EliosMode as Mode, EliosState};
+use crate::elios::{FAN_SHIFT, POWER_BIT, SLEEP_BIT};
+
+const FAHRENHEIT_FLAG: u8 = 0b10000000;
+
+/// Packs an optional temperature into one byte: the unit flag in the high bit and the degrees
+/// below it, 0 meaning no temperature.
+pub(crate) fn temperature_to_byte(temperature: Option<Temperature>) -> u8 {
+    match temperature {
+        Some(Temperature::Celcius(temp)) => temp & !FAHRENHEIT_FLAG,
+        Some(Temperature::Fahrenheit(temp)) => temp & !FAHRENHEIT_FLAG | FAHRENHEIT_FLAG,
+        None => 0,
+    }
+}
+
+pub(crate) fn temperature_from_byte(byte: u8) -> Option<Temperature> {
+    let temp = byte & !FAHRENHEIT_FLAG;
+
+    match (byte & FAHRENHEIT_FLAG != 0, temp) {
+        (false, 0) => None,
+        (false, _) => Some(Temperature::Celcius(temp)),
+        (true, _) => Some(Temperature::Fahrenheit(temp)),
+    }
+}
+
+impl EliosState {
+    /// Packs the state into two bytes for storage: the options byte of the frame followed by
+    /// the temperature. This is not a wire format, see `as_bytes` for the frame itself.
+    pub fn to_compact(self) -> [u8; 2] {
+        [
+            (self.powered() as u8) << POWER_BIT
+                | (self.sleep() as u8) << SLEEP_BIT
+                | self.fan_speed().as_bits() << FAN_SHIFT
+                | self.mode().as_bits(),
+            temperature_to_byte(self.temperature()),
+        ]
+    }
+
+    /// Unpacks a state stored with `to_compact`, `None` if the bytes do not hold a valid state.
+    pub fn from_compact(bytes: [u8; 2]) -> Option<Self> {
+        let state = Self::new(
+            Some(FanSpeed::from_bits(bytes[0] >> FAN_SHIFT & 0b111)?),
+            Mode::from_bits(bytes[0] & 0b111)?,
+            temperature_from_byte(bytes[1]),
+            bytes[0] >> POWER_BIT & 1 == 1,
+            bytes[0] >> SLEEP_BIT & 1 == 1,
+        )?;
+
+        if state.to_compact() == bytes {
+            Some(state)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn given_every_valid_state_then_compact_form_round_trips() {
+        for state in EliosState::all_valid() {
+            assert_eq!(EliosState::from_compact(state.to_compact()), Some(state));
+        }
+    }
+
+    #[test]
+    fn given_out_of_range_temperature_then_compact_form_is_rejected() {
+        let mut bytes = EliosState::cool(Temperature::Celcius(22)).to_compact();
+        bytes[1] = 35;
+
+        assert_eq!(EliosState::from_compact(bytes), None);
+    }
+}
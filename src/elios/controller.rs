@@ -0,0 +1,346 @@
+use crate::common::{Temperature, TemperatureUnit};
+use crate::elios::compact::{temperature_from_byte, temperature_to_byte};
+use crate::elios::state::{convert_degrees, step_temperature, DEFAULT_TEMPERATURE};
+use crate::elios::{EliosFanSpeed as FanSpeed, EliosMode as Mode, EliosState, StateError};
+
+fn degrees(temperature: Temperature, unit: TemperatureUnit) -> f32 {
+    let value = match temperature {
+        Temperature::Celcius(temp) | Temperature::Fahrenheit(temp) => temp as f32,
+    };
+
+    convert_degrees(value, temperature.unit(), unit)
+}
+
+/// Stateful convenience layer over `EliosState` that remembers the last command sent, so that
+/// relative changes such as toggles can be expressed. Like the physical remote, it also remembers
+/// the last setpoint of each mode.
+#[derive(Debug, Clone)]
+pub struct EliosController {
+    state: EliosState,
+    setpoints: [Option<Temperature>; 5],
+    last_error: Option<StateError>,
+}
+
+impl EliosController {
+    pub fn new(state: EliosState) -> Self {
+        let mut controller = Self {
+            state,
+            setpoints: [None; 5],
+            last_error: None,
+        };
+        controller.update(state);
+
+        controller
+    }
+
+    pub fn state(&self) -> EliosState {
+        self.state
+    }
+
+    /// Why the last change returned `None`, cleared by the next successful change.
+    pub fn last_error(&self) -> Option<&StateError> {
+        self.last_error.as_ref()
+    }
+
+    /// Flips the sleep setting and returns the new frame, or `None` if the current mode has no
+    /// sleep setting.
+    pub fn toggle_sleep(&mut self) -> Option<u64> {
+        if !self.state.mode().supports_sleep() {
+            return self.fail(StateError::SleepUnsupported(self.state.mode()));
+        }
+
+        self.apply(EliosState::try_new(
+            Some(self.state.fan_speed()),
+            self.state.mode(),
+            self.state.temperature(),
+            self.state.powered(),
+            !self.state.sleep(),
+        ))
+    }
+
+    /// Moves the temperature by `delta` degrees in its current unit, clamped to the supported
+    /// range. Returns `None` in fan mode.
+    pub fn bump_temperature(&mut self, delta: i8) -> Option<u64> {
+        let temperature = match self.state.temperature() {
+            Some(temperature) => step_temperature(temperature, delta),
+            None => return self.fail(StateError::TemperatureInFanMode),
+        };
+
+        self.apply(EliosState::try_new(
+            Some(self.state.fan_speed()),
+            self.state.mode(),
+            Some(temperature),
+            self.state.powered(),
+            self.state.sleep(),
+        ))
+    }
+
+    /// Moves the temperature by `delta` degrees of `unit`, whatever unit the setpoint is stored
+    /// in, snapping the result to the nearest setpoint in the stored unit. Returns `None` in fan
+    /// mode.
+    pub fn adjust_temperature_by(&mut self, delta: f32, unit: TemperatureUnit) -> Option<u64> {
+        let current = match self.state.temperature() {
+            Some(temperature) => temperature,
+            None => return self.fail(StateError::TemperatureInFanMode),
+        };
+        let stored = current.unit();
+
+        let adjusted = convert_degrees(degrees(current, unit) + delta, unit, stored);
+
+        self.apply(EliosState::try_new(
+            Some(self.state.fan_speed()),
+            self.state.mode(),
+            Some(Temperature::snap(adjusted, stored)),
+            self.state.powered(),
+            self.state.sleep(),
+        ))
+    }
+
+    /// Serializes the last state and the remembered setpoints, to resume after a restart.
+    pub fn save(&self) -> Vec<u8> {
+        let mut bytes = self.state.to_compact().to_vec();
+        bytes.extend(
+            self.setpoints
+                .iter()
+                .map(|setpoint| temperature_to_byte(*setpoint)),
+        );
+
+        bytes
+    }
+
+    /// Restores a controller serialized with `save`.
+    pub fn load(bytes: &[u8]) -> Option<EliosController> {
+        if bytes.len() != 2 + 5 {
+            return None;
+        }
+
+        let state = EliosState::from_compact([bytes[0], bytes[1]])?;
+        let mut setpoints = [None; 5];
+
+        for (setpoint, byte) in setpoints.iter_mut().zip(&bytes[2..]) {
+            *setpoint = temperature_from_byte(*byte);
+        }
+
+        Some(Self {
+            state,
+            setpoints,
+            last_error: None,
+        })
+    }
+
+    /// The last setpoint used in the given mode, if any.
+    pub fn setpoint(&self, mode: Mode) -> Option<Temperature> {
+        self.setpoints[mode.as_bits() as usize]
+    }
+
+    /// Switches mode, restoring the setpoint last used in that mode, and returns the new frame.
+    pub fn set_mode(&mut self, mode: Mode) -> Option<u64> {
+        let temperature = if mode == Mode::Fan {
+            None
+        } else {
+            self.setpoint(mode)
+                .or_else(|| self.state.temperature())
+                .or_else(|| self.setpoints.iter().flatten().next().copied())
+                .or(Some(DEFAULT_TEMPERATURE))
+        };
+
+        let fan_speed = match (mode, self.state.fan_speed()) {
+            (Mode::Automatic, _) | (Mode::Dry, _) | (_, FanSpeed::Off) => None,
+            (_, fan_speed) => Some(fan_speed),
+        };
+
+        self.apply(EliosState::try_new(
+            fan_speed,
+            mode,
+            temperature,
+            self.state.powered(),
+            self.state.sleep(),
+        ))
+    }
+
+    fn apply(&mut self, state: Result<EliosState, StateError>) -> Option<u64> {
+        match state {
+            Ok(state) => Some(self.update(state)),
+            Err(error) => self.fail(error),
+        }
+    }
+
+    fn fail(&mut self, error: StateError) -> Option<u64> {
+        self.last_error = Some(error);
+
+        None
+    }
+
+    fn update(&mut self, state: EliosState) -> u64 {
+        self.state = state;
+        self.last_error = None;
+
+        if let Some(temperature) = state.temperature() {
+            self.setpoints[state.mode().as_bits() as usize] = Some(temperature);
+        }
+
+        state.as_value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cold(temperature: u8) -> EliosState {
+        EliosState::new(
+            Some(FanSpeed::Automatic),
+            Mode::Cold,
+            Some(Temperature::Celcius(temperature)),
+            true,
+            false,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn when_sleep_toggled_twice_then_state_is_restored() {
+        let mut controller = EliosController::new(cold(22));
+
+        let frame = controller.toggle_sleep().unwrap();
+
+        assert!(controller.state().sleep());
+        assert_eq!(EliosState::from_value(frame), Some(controller.state()));
+
+        controller.toggle_sleep().unwrap();
+
+        assert_eq!(controller.state(), cold(22));
+    }
+
+    #[test]
+    fn given_dry_mode_when_sleep_toggled_then_nothing_is_sent() {
+        let dry =
+            EliosState::new(None, Mode::Dry, Some(Temperature::Celcius(22)), true, false).unwrap();
+        let mut controller = EliosController::new(dry);
+
+        assert_eq!(controller.toggle_sleep(), None);
+        assert_eq!(controller.state(), dry);
+    }
+
+    #[test]
+    fn when_temperature_bumped_then_is_clamped_to_range() {
+        let mut controller = EliosController::new(cold(29));
+
+        assert_eq!(controller.bump_temperature(1), Some(cold(30).as_value()));
+        assert_eq!(controller.bump_temperature(5), Some(cold(30).as_value()));
+        assert_eq!(controller.bump_temperature(-3), Some(cold(27).as_value()));
+    }
+
+    #[test]
+    fn given_fan_mode_when_temperature_bumped_then_nothing_is_sent() {
+        let fan = EliosState::new(Some(FanSpeed::Low), Mode::Fan, None, true, false).unwrap();
+        let mut controller = EliosController::new(fan);
+
+        assert_eq!(controller.bump_temperature(1), None);
+    }
+
+    #[test]
+    fn when_switching_modes_then_each_mode_setpoint_is_restored() {
+        let mut controller = EliosController::new(cold(22));
+
+        controller.set_mode(Mode::Heat).unwrap();
+        assert_eq!(
+            controller.state().temperature(),
+            Some(Temperature::Celcius(22))
+        );
+
+        controller.bump_temperature(4).unwrap();
+        controller.set_mode(Mode::Cold).unwrap();
+        assert_eq!(
+            controller.state().temperature(),
+            Some(Temperature::Celcius(22))
+        );
+
+        controller.set_mode(Mode::Heat).unwrap();
+        assert_eq!(
+            controller.state().temperature(),
+            Some(Temperature::Celcius(26))
+        );
+        assert_eq!(
+            controller.setpoint(Mode::Cold),
+            Some(Temperature::Celcius(22))
+        );
+    }
+
+    #[test]
+    fn when_switching_through_fan_mode_then_setpoint_is_kept() {
+        let mut controller = EliosController::new(cold(19));
+
+        controller.set_mode(Mode::Fan).unwrap();
+        assert_eq!(controller.state().temperature(), None);
+
+        controller.set_mode(Mode::Automatic).unwrap();
+        assert_eq!(
+            controller.state().temperature(),
+            Some(Temperature::Celcius(19))
+        );
+        assert_eq!(controller.state().fan_speed(), FanSpeed::Off);
+    }
+
+    #[test]
+    fn when_saved_and_loaded_then_state_and_setpoints_are_restored() {
+        let mut controller = EliosController::new(cold(22));
+        controller.set_mode(Mode::Heat).unwrap();
+        controller.bump_temperature(-2).unwrap();
+
+        let loaded = EliosController::load(&controller.save()).unwrap();
+
+        assert_eq!(loaded.state(), controller.state());
+        for mode in Mode::ALL.iter() {
+            assert_eq!(loaded.setpoint(*mode), controller.setpoint(*mode));
+        }
+        assert_eq!(loaded.setpoint(Mode::Cold), Some(Temperature::Celcius(22)));
+    }
+
+    #[test]
+    fn given_truncated_bytes_then_controller_is_not_loaded() {
+        let bytes = EliosController::new(cold(22)).save();
+
+        assert!(EliosController::load(&bytes[..3]).is_none());
+    }
+
+    #[test]
+    fn when_celcius_setpoint_adjusted_in_fahrenheit_then_stays_celcius() {
+        let mut controller = EliosController::new(cold(22));
+
+        controller
+            .adjust_temperature_by(2.0, TemperatureUnit::Fahrenheit)
+            .unwrap();
+
+        assert_eq!(controller.state(), cold(23));
+    }
+
+    #[test]
+    fn when_fahrenheit_setpoint_adjusted_in_celcius_then_stays_fahrenheit() {
+        let mut controller = EliosController::new(EliosState::heat(Temperature::Fahrenheit(70)));
+
+        controller
+            .adjust_temperature_by(-1.0, TemperatureUnit::Celcius)
+            .unwrap();
+
+        assert_eq!(
+            controller.state().temperature(),
+            Some(Temperature::Fahrenheit(68))
+        );
+    }
+
+    #[test]
+    fn when_change_rejected_then_last_error_tells_why_until_next_success() {
+        let mut controller = EliosController::new(EliosState::dry(Temperature::Celcius(22)));
+
+        assert_eq!(controller.toggle_sleep(), None);
+        assert_eq!(
+            controller.last_error(),
+            Some(&StateError::SleepUnsupported(Mode::Dry))
+        );
+
+        controller.bump_temperature(1).unwrap();
+
+        assert_eq!(controller.last_error(), None);
+    }
+}
@@ -0,0 +1,123 @@
+use crate::common::{Temperature, TemperatureUnit};
+use crate::elios::json::{fan_speed_name, mode_name};
+use crate::elios::{EliosFanSpeed as FanSpeed, EliosMode as Mode, EliosState, StateError};
+
+const HEADER: &str = "mode,temperature,fan,power,sleep";
+
+fn parse_temperature(field: &str) -> Result<Option<Temperature>, StateError> {
+    let invalid = StateError::InvalidField("temperature");
+
+    if field.is_empty() {
+        return Ok(None);
+    }
+
+    let (degrees, unit) = if let Some(degrees) = field.strip_suffix(['C', 'c']) {
+        (degrees, TemperatureUnit::Celcius)
+    } else if let Some(degrees) = field.strip_suffix(['F', 'f']) {
+        (degrees, TemperatureUnit::Fahrenheit)
+    } else {
+        return Err(invalid);
+    };
+    let degrees = degrees.parse().map_err(|_| invalid)?;
+
+    Ok(Some(match unit {
+        TemperatureUnit::Celcius => Temperature::Celcius(degrees),
+        TemperatureUnit::Fahrenheit => Temperature::Fahrenheit(degrees),
+    }))
+}
+
+fn parse_bool(field: &str, name: &'static str) -> Result<bool, StateError> {
+    match field {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err(StateError::InvalidField(name)),
+    }
+}
+
+fn parse_row(row: &str) -> Result<EliosState, StateError> {
+    let fields: Vec<&str> = row.split(',').map(str::trim).collect();
+
+    if fields.len() != 5 {
+        return Err(StateError::InvalidField("row"));
+    }
+
+    let mode = *Mode::ALL
+        .iter()
+        .find(|mode| mode_name(**mode) == fields[0])
+        .ok_or(StateError::InvalidField("mode"))?;
+    let fan_speed = match fields[2] {
+        "" => None,
+        name => Some(
+            *FanSpeed::ALL
+                .iter()
+                .find(|fan_speed| fan_speed_name(**fan_speed) == name)
+                .ok_or(StateError::InvalidField("fan"))?,
+        ),
+    };
+
+    EliosState::try_new(
+        fan_speed,
+        mode,
+        parse_temperature(fields[1])?,
+        parse_bool(fields[3], "power")?,
+        parse_bool(fields[4], "sleep")?,
+    )
+}
+
+/// Parses and validates a schedule with one state per row, in the columns
+/// `mode,temperature,fan,power,sleep`. Names are the ones used in JSON, temperatures carry their
+/// unit (`22C`, `72F`) and empty fields mean none. A leading header row and blank lines are
+/// skipped, and failures carry their 1-based line number.
+pub fn parse_and_validate_csv(contents: &str) -> Vec<Result<EliosState, (usize, StateError)>> {
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(index, line)| {
+            let header = *index == 0 && line.trim() == HEADER;
+
+            !header && !line.trim().is_empty()
+        })
+        .map(|(index, line)| parse_row(line).map_err(|error| (index + 1, error)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn given_mixed_rows_then_each_is_validated_with_its_line_number() {
+        let contents = "mode,temperature,fan,power,sleep\n\
+                        cold,22C,low,true,false\n\
+                        fan,22C,high,true,false\n\
+                        \n\
+                        heat,72F,,true,true\n\
+                        dry,24C,high,true,false\n\
+                        auto,24C,,true,false\n";
+
+        assert_eq!(
+            parse_and_validate_csv(contents),
+            vec![
+                EliosState::try_new(
+                    Some(FanSpeed::Low),
+                    Mode::Cold,
+                    Some(Temperature::Celcius(22)),
+                    true,
+                    false
+                )
+                .map_err(|error| (2, error)),
+                Err((3, StateError::TemperatureInFanMode)),
+                EliosState::try_new(
+                    None,
+                    Mode::Heat,
+                    Some(Temperature::Fahrenheit(72)),
+                    true,
+                    true
+                )
+                .map_err(|error| (5, error)),
+                Err((6, StateError::FanSpeedInManagedMode(Mode::Dry))),
+                Err((7, StateError::InvalidField("mode"))),
+            ]
+        );
+    }
+}
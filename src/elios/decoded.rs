@@ -0,0 +1,245 @@
+use crate::common::Temperature;
+use crate::elios::state::{
+    FAN_TEMPERATURE, MAX_CELCIUS, MAX_FAHRENHEIT, MIN_CELCIUS, MIN_FAHRENHEIT, RESERVED_BITS,
+};
+use crate::elios::{EliosFanSpeed as FanSpeed, EliosMode as Mode, EliosState};
+use crate::elios::{FAN_SHIFT, POWER_BIT, SLEEP_BIT, UNIT_FLAG_BIT};
+
+const HEADER: u8 = 0b10100001;
+
+/// The raw fields of a frame, decoded without any validation. Unlike `EliosState::from_value`
+/// this never fails, which makes it suitable for protocol research on odd captures.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct DecodedFrame {
+    pub header: u8,
+    pub powered: bool,
+    pub sleep: bool,
+    pub fan_bits: u8,
+    pub mode_bits: u8,
+    /// The two high bits of the temperature byte, of unknown meaning
+    pub reserved_bits: u8,
+    pub fahrenheit: bool,
+    /// The temperature as an offset from the unit's minimum
+    pub temperature_bits: u8,
+    pub off_timer: u8,
+    pub on_timer: u8,
+    pub checksum: u8,
+    /// Whether `checksum` matches the one computed over the data bytes
+    pub checksum_valid: bool,
+}
+
+impl DecodedFrame {
+    /// Splits the low 48 bits of `value` into fields.
+    pub fn from_value(value: u64) -> DecodedFrame {
+        let byte = |index: u32| (value >> (40 - 8 * index)) as u8;
+        let data = [byte(0), byte(1), byte(2), byte(3), byte(4)];
+
+        DecodedFrame {
+            header: data[0],
+            powered: data[1] >> POWER_BIT & 1 == 1,
+            sleep: data[1] >> SLEEP_BIT & 1 == 1,
+            fan_bits: data[1] >> FAN_SHIFT & 0b111,
+            mode_bits: data[1] & 0b111,
+            reserved_bits: data[2] >> 6,
+            fahrenheit: data[2] >> UNIT_FLAG_BIT & 1 == 1,
+            temperature_bits: data[2] & 0b11111,
+            off_timer: data[3],
+            on_timer: data[4],
+            checksum: byte(5),
+            checksum_valid: EliosState::checksum(&data) == byte(5),
+        }
+    }
+
+    /// Reassembles the fields into a frame, keeping the original checksum, so that captures with
+    /// unusual bits round-trip exactly.
+    pub fn to_value(&self) -> u64 {
+        let data = [
+            self.header,
+            (self.powered as u8) << POWER_BIT
+                | (self.sleep as u8) << SLEEP_BIT
+                | (self.fan_bits & 0b111) << FAN_SHIFT
+                | self.mode_bits & 0b111,
+            self.reserved_bits << 6
+                | (self.fahrenheit as u8) << UNIT_FLAG_BIT
+                | self.temperature_bits & 0b11111,
+            self.off_timer,
+            self.on_timer,
+            self.checksum,
+        ];
+
+        data.iter().fold(0, |acc, x| acc << 8 | *x as u64)
+    }
+
+    pub fn fan_speed(&self) -> Option<FanSpeed> {
+        FanSpeed::from_bits(self.fan_bits)
+    }
+
+    pub fn mode(&self) -> Option<Mode> {
+        Mode::from_bits(self.mode_bits)
+    }
+
+    /// The setpoint the temperature bits stand for. Like `as_value`, only the low 5 bits of
+    /// `temperature_bits` are used.
+    pub fn temperature(&self) -> Temperature {
+        let offset = self.temperature_bits & 0b11111;
+
+        if self.fahrenheit {
+            Temperature::Fahrenheit(offset + MIN_FAHRENHEIT)
+        } else {
+            Temperature::Celcius(offset + MIN_CELCIUS)
+        }
+    }
+
+    /// Field combinations `EliosState::new` would never produce. A frame can pass the checksum
+    /// and still be corrupted in ways only these reveal.
+    pub fn consistency_issues(&self) -> Vec<&'static str> {
+        let mut issues = Vec::new();
+
+        if self.header != HEADER {
+            issues.push("unknown header");
+        }
+
+        if self.reserved_bits != RESERVED_BITS {
+            issues.push("unknown reserved bits");
+        }
+
+        if self.fan_speed().is_none() {
+            issues.push("unknown fan speed");
+        }
+
+        match self.mode() {
+            None => issues.push("unknown mode"),
+            Some(mode) => {
+                if (mode == Mode::Automatic || mode == Mode::Dry) && self.fan_bits != 0 {
+                    issues.push("fan speed set in a mode where the unit manages the fan");
+                }
+
+                if self.sleep && !mode.supports_sleep() {
+                    issues.push("sleep set in a mode without sleep");
+                }
+
+                let out_of_range = match self.temperature() {
+                    Temperature::Celcius(temp) => temp > MAX_CELCIUS,
+                    Temperature::Fahrenheit(temp) => temp > MAX_FAHRENHEIT,
+                };
+
+                if mode == Mode::Fan {
+                    if self.fahrenheit || self.temperature_bits != FAN_TEMPERATURE {
+                        issues.push("temperature set in fan mode");
+                    }
+                } else if out_of_range {
+                    issues.push("temperature out of range");
+                }
+            }
+        }
+
+        if self.off_timer != 0xff || self.on_timer != 0xff {
+            issues.push("timer set");
+        }
+
+        issues
+    }
+}
+
+impl EliosState {
+    /// Forgiving counterpart to `from_value` for research: every field is kept, unknown fan and
+    /// mode bits stay raw and the checksum is only reported, so this never fails.
+    pub fn try_from_bytes_lenient(bytes: [u8; 6]) -> DecodedFrame {
+        DecodedFrame::from_value(bytes.iter().fold(0, |acc, x| acc << 8 | *x as u64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(data: [u8; 5]) -> u64 {
+        let checksum = EliosState::checksum(&data);
+
+        data.iter().fold(0, |acc, x| acc << 8 | *x as u64) << 8 | checksum as u64
+    }
+
+    #[test]
+    fn given_valid_frame_then_fields_are_decoded_without_issues() {
+        let state = EliosState::heat(Temperature::Fahrenheit(70));
+
+        let decoded = DecodedFrame::from_value(state.as_value());
+
+        assert!(decoded.checksum_valid);
+        assert_eq!(decoded.mode(), Some(Mode::Heat));
+        assert_eq!(decoded.fan_speed(), Some(FanSpeed::Automatic));
+        assert_eq!(decoded.temperature(), Temperature::Fahrenheit(70));
+        assert!(decoded.consistency_issues().is_empty());
+    }
+
+    #[test]
+    fn given_checksum_valid_but_inconsistent_frame_then_issues_are_reported() {
+        // automatic mode with a high fan speed and sleep, which `new` rejects
+        let value = frame([HEADER, 0b11011010, 0b01000101, 0xff, 0xff]);
+
+        let decoded = DecodedFrame::from_value(value);
+
+        assert!(decoded.checksum_valid);
+        assert_eq!(EliosState::from_value(value), None);
+        assert_eq!(
+            decoded.consistency_issues(),
+            vec!["fan speed set in a mode where the unit manages the fan"]
+        );
+    }
+
+    #[test]
+    fn given_out_of_field_temperature_bits_then_only_the_low_five_bits_are_used() {
+        let mut decoded = DecodedFrame::from_value(EliosState::fan_only().as_value());
+        decoded.fahrenheit = true;
+        decoded.temperature_bits = 0xff;
+
+        assert_eq!(
+            decoded.temperature(),
+            Temperature::Fahrenheit(31 + MIN_FAHRENHEIT)
+        );
+    }
+
+    #[test]
+    fn given_garbage_frame_then_every_issue_is_reported() {
+        let decoded = DecodedFrame::from_value(frame([0, 0b00111111, 0b11011111, 0, 0]));
+
+        assert_eq!(
+            decoded.consistency_issues(),
+            vec![
+                "unknown header",
+                "unknown reserved bits",
+                "unknown fan speed",
+                "unknown mode",
+                "timer set"
+            ]
+        );
+    }
+
+    #[test]
+    fn given_each_reserved_bits_combination_then_frame_round_trips() {
+        for reserved in 0..4 {
+            let value = frame([HEADER, 0b10100000, reserved << 6 | 0b00101, 0xff, 0xff]);
+
+            let decoded = DecodedFrame::from_value(value);
+
+            assert_eq!(decoded.reserved_bits, reserved);
+            assert_eq!(decoded.temperature(), Temperature::Celcius(22));
+            assert!(decoded.checksum_valid);
+            assert_eq!(decoded.to_value(), value);
+        }
+    }
+
+    #[test]
+    fn given_bytes_with_unknown_fan_then_lenient_decoding_keeps_raw_bits() {
+        let mut bytes = EliosState::cool(Temperature::Celcius(22)).as_bytes();
+        bytes[1] |= 0b111 << 3;
+
+        let decoded = EliosState::try_from_bytes_lenient(bytes);
+
+        assert_eq!(decoded.fan_bits, 0b111);
+        assert_eq!(decoded.fan_speed(), None);
+        assert_eq!(decoded.mode(), Some(Mode::Cold));
+        assert_eq!(decoded.temperature(), Temperature::Celcius(22));
+        assert!(!decoded.checksum_valid);
+    }
+}
@@ -0,0 +1,97 @@
+use crate::common::Temperature;
+use crate::elios::{EliosFanSpeed as FanSpeed, EliosMode as Mode, EliosState};
+
+fn fan_speed_phrase(fan_speed: FanSpeed) -> &'static str {
+    match fan_speed {
+        FanSpeed::Off => "fan off",
+        FanSpeed::Automatic => "automatic fan",
+        FanSpeed::Low => "low fan",
+        FanSpeed::Medium => "medium fan",
+        FanSpeed::High => "high fan",
+    }
+}
+
+fn degrees(temperature: Option<Temperature>) -> u8 {
+    match temperature {
+        Some(Temperature::Celcius(temp)) | Some(Temperature::Fahrenheit(temp)) => temp,
+        None => 0,
+    }
+}
+
+impl EliosState {
+    /// A short sentence describing the command, e.g. for voice assistant feedback.
+    pub fn describe(self) -> String {
+        if !self.powered() {
+            return "Turned off".to_string();
+        }
+
+        let temp = degrees(self.temperature());
+        let fan = fan_speed_phrase(self.fan_speed());
+
+        let phrase = match self.mode() {
+            Mode::Cold => format!("Cooling to {} degrees, {}", temp, fan),
+            Mode::Heat => format!("Heating to {} degrees, {}", temp, fan),
+            Mode::Automatic => format!("Automatic mode at {} degrees", temp),
+            Mode::Dry => format!("Drying at {} degrees", temp),
+            Mode::Fan => format!("Fan only, {}", fan),
+        };
+
+        if self.sleep() {
+            format!("{}, sleep on", phrase)
+        } else {
+            phrase
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn given_cooling_state_then_description_mentions_temperature_and_fan() {
+        let state = EliosState::cool(Temperature::Celcius(22));
+
+        assert_eq!(state.describe(), "Cooling to 22 degrees, automatic fan");
+    }
+
+    #[test]
+    fn given_powered_off_state_then_description_is_turned_off() {
+        let state = EliosState::new(
+            Some(FanSpeed::High),
+            Mode::Heat,
+            Some(Temperature::Celcius(26)),
+            false,
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(state.describe(), "Turned off");
+    }
+
+    #[test]
+    fn given_fan_and_dry_states_then_descriptions_are_specific() {
+        let fan = EliosState::new(Some(FanSpeed::Low), Mode::Fan, None, true, false).unwrap();
+        let dry = EliosState::dry(Temperature::Fahrenheit(75));
+
+        assert_eq!(fan.describe(), "Fan only, low fan");
+        assert_eq!(dry.describe(), "Drying at 75 degrees");
+    }
+
+    #[test]
+    fn given_sleeping_state_then_description_mentions_sleep() {
+        let state = EliosState::new(
+            Some(FanSpeed::Medium),
+            Mode::Heat,
+            Some(Temperature::Celcius(21)),
+            true,
+            true,
+        )
+        .unwrap();
+
+        assert_eq!(
+            state.describe(),
+            "Heating to 21 degrees, medium fan, sleep on"
+        );
+    }
+}
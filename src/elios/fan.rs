@@ -1,4 +1,4 @@
-#[derive(Debug, PartialEq, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
 pub enum EliosFanSpeed {
     Off = 0b000,
     Automatic = 0b100,
@@ -6,3 +6,51 @@ pub enum EliosFanSpeed {
     Medium = 0b010,
     High = 0b011,
 }
+
+impl EliosFanSpeed {
+    pub const ALL: [Self; 5] = [
+        EliosFanSpeed::Off,
+        EliosFanSpeed::Automatic,
+        EliosFanSpeed::Low,
+        EliosFanSpeed::Medium,
+        EliosFanSpeed::High,
+    ];
+
+    pub fn as_bits(self) -> u8 {
+        self as u8
+    }
+
+    pub fn from_bits(bits: u8) -> Option<Self> {
+        match bits {
+            0b000 => Some(EliosFanSpeed::Off),
+            0b100 => Some(EliosFanSpeed::Automatic),
+            0b001 => Some(EliosFanSpeed::Low),
+            0b010 => Some(EliosFanSpeed::Medium),
+            0b011 => Some(EliosFanSpeed::High),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn when_as_bits_then_protocol_code_is_returned() {
+        assert_eq!(EliosFanSpeed::Off.as_bits(), 0b000);
+        assert_eq!(EliosFanSpeed::Automatic.as_bits(), 0b100);
+        assert_eq!(EliosFanSpeed::Low.as_bits(), 0b001);
+        assert_eq!(EliosFanSpeed::Medium.as_bits(), 0b010);
+        assert_eq!(EliosFanSpeed::High.as_bits(), 0b011);
+    }
+
+    #[test]
+    fn given_bits_then_variant_is_recovered() {
+        for bits in 0..8 {
+            if let Some(value) = EliosFanSpeed::from_bits(bits) {
+                assert_eq!(value.as_bits(), bits);
+            }
+        }
+    }
+}
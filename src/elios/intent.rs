@@ -0,0 +1,87 @@
+use crate::elios::EliosState;
+
+/// What a frame does to the unit, given the state it was in.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Intent {
+    TurnOn,
+    TurnOff,
+    Reconfigure,
+    NoChange,
+}
+
+impl EliosState {
+    /// Classifies this frame against `previous`, the last state sent to the unit if known. A
+    /// unit of unknown state is assumed to follow the power bit, and settings carried by a
+    /// frame that keeps the unit off are not considered a change.
+    pub fn intent(self, previous: Option<EliosState>) -> Intent {
+        match (previous.map(|state| state.powered()), self.powered()) {
+            (Some(true), true) if previous == Some(self) => Intent::NoChange,
+            (Some(true), true) => Intent::Reconfigure,
+            (Some(false), false) => Intent::NoChange,
+            (_, true) => Intent::TurnOn,
+            (_, false) => Intent::TurnOff,
+        }
+    }
+}
+
+/// The frames to send to two units so that both end up in state `a`, `None` for a unit that is
+/// already there. Unit `a` defines the target, so only unit `b` can need a frame.
+pub fn sync_frames(a: EliosState, b: EliosState) -> (Option<u64>, Option<u64>) {
+    let frame = |state: EliosState| Some(a.as_value()).filter(|_| state != a);
+
+    (frame(a), frame(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::Temperature;
+    use crate::elios::EliosMode as Mode;
+
+    fn off(temp: u8) -> EliosState {
+        EliosState::new(
+            None,
+            Mode::Cold,
+            Some(Temperature::Celcius(temp)),
+            false,
+            false,
+        )
+        .unwrap()
+    }
+
+    fn on(temp: u8) -> EliosState {
+        EliosState::cool(Temperature::Celcius(temp))
+    }
+
+    #[test]
+    fn given_unit_off_when_powered_frame_then_intent_is_turn_on() {
+        assert_eq!(on(22).intent(Some(off(22))), Intent::TurnOn);
+        assert_eq!(on(22).intent(None), Intent::TurnOn);
+    }
+
+    #[test]
+    fn given_unit_on_when_unpowered_frame_then_intent_is_turn_off() {
+        assert_eq!(off(22).intent(Some(on(22))), Intent::TurnOff);
+        assert_eq!(off(22).intent(None), Intent::TurnOff);
+    }
+
+    #[test]
+    fn given_unit_on_when_settings_change_then_intent_is_reconfigure() {
+        assert_eq!(on(23).intent(Some(on(22))), Intent::Reconfigure);
+    }
+
+    #[test]
+    fn given_same_state_or_unit_kept_off_then_intent_is_no_change() {
+        assert_eq!(on(22).intent(Some(on(22))), Intent::NoChange);
+        assert_eq!(off(23).intent(Some(off(22))), Intent::NoChange);
+    }
+
+    #[test]
+    fn given_units_out_of_sync_then_only_the_differing_one_gets_a_frame() {
+        assert_eq!(
+            sync_frames(on(22), off(24)),
+            (None, Some(on(22).as_value()))
+        );
+        assert_eq!(sync_frames(on(22), on(22)), (None, None));
+    }
+}
@@ -0,0 +1,107 @@
+use crate::common::{Temperature, TemperatureUnit};
+use crate::elios::{EliosFanSpeed as FanSpeed, EliosMode as Mode, EliosState};
+
+pub(crate) fn mode_name(mode: Mode) -> &'static str {
+    match mode {
+        Mode::Cold => "cold",
+        Mode::Dry => "dry",
+        Mode::Automatic => "automatic",
+        Mode::Heat => "heat",
+        Mode::Fan => "fan",
+    }
+}
+
+pub(crate) fn fan_speed_name(fan_speed: FanSpeed) -> &'static str {
+    match fan_speed {
+        FanSpeed::Off => "off",
+        FanSpeed::Automatic => "automatic",
+        FanSpeed::Low => "low",
+        FanSpeed::Medium => "medium",
+        FanSpeed::High => "high",
+    }
+}
+
+fn temperature_value(temperature: Option<Temperature>) -> String {
+    match temperature {
+        Some(Temperature::Celcius(temp)) | Some(Temperature::Fahrenheit(temp)) => temp.to_string(),
+        None => "null".to_string(),
+    }
+}
+
+fn temperature_unit(temperature: Option<Temperature>) -> &'static str {
+    match temperature.map(|temp| temp.unit()) {
+        Some(TemperatureUnit::Celcius) => "\"celcius\"",
+        Some(TemperatureUnit::Fahrenheit) => "\"fahrenheit\"",
+        None => "null",
+    }
+}
+
+impl EliosState {
+    /// A JSON object holding only the fields that differ from `previous`, for publishing
+    /// attribute changes over MQTT.
+    pub fn json_delta(self, previous: EliosState) -> String {
+        let mut fields = Vec::new();
+
+        if self.powered() != previous.powered() {
+            fields.push(format!("\"powered\":{}", self.powered()));
+        }
+
+        if self.mode() != previous.mode() {
+            fields.push(format!("\"mode\":\"{}\"", mode_name(self.mode())));
+        }
+
+        if self.fan_speed() != previous.fan_speed() {
+            fields.push(format!(
+                "\"fan_speed\":\"{}\"",
+                fan_speed_name(self.fan_speed())
+            ));
+        }
+
+        let value = temperature_value(self.temperature());
+        if value != temperature_value(previous.temperature()) {
+            fields.push(format!("\"temperature\":{}", value));
+        }
+
+        let unit = temperature_unit(self.temperature());
+        if unit != temperature_unit(previous.temperature()) {
+            fields.push(format!("\"temperature_unit\":{}", unit));
+        }
+
+        if self.sleep() != previous.sleep() {
+            fields.push(format!("\"sleep\":{}", self.sleep()));
+        }
+
+        format!("{{{}}}", fields.join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn given_only_temperature_changed_then_delta_contains_only_temperature() {
+        let previous = EliosState::cool(Temperature::Celcius(22));
+        let state = EliosState::cool(Temperature::Celcius(24));
+
+        assert_eq!(state.json_delta(previous), "{\"temperature\":24}");
+    }
+
+    #[test]
+    fn given_identical_states_then_delta_is_empty() {
+        let state = EliosState::cool(Temperature::Celcius(22));
+
+        assert_eq!(state.json_delta(state), "{}");
+    }
+
+    #[test]
+    fn given_mode_changed_to_fan_then_delta_contains_every_affected_field() {
+        let previous = EliosState::auto(Temperature::Fahrenheit(72));
+        let state = EliosState::fan_only();
+
+        assert_eq!(
+            state.json_delta(previous),
+            "{\"mode\":\"fan\",\"fan_speed\":\"automatic\",\"temperature\":null,\"temperature_unit\":null}"
+        );
+    }
+}
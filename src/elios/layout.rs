@@ -0,0 +1,109 @@
+use std::ops::Range;
+
+use crate::common::{ClimateState, Temperature};
+use crate::elios::json::{fan_speed_name, mode_name};
+use crate::elios::EliosState;
+
+/// Bit ranges of each field, 0 being the first bit sent.
+const FIELD_LAYOUT: [(&str, Range<usize>); 11] = [
+    ("Header", 0..8),
+    ("Power", 8..9),
+    ("Sleep", 9..10),
+    ("Fan speed", 10..13),
+    ("Mode", 13..16),
+    ("Reserved", 16..18),
+    ("Unit", 18..19),
+    ("Temperature", 19..24),
+    ("Off timer", 24..32),
+    ("On timer", 32..40),
+    ("Checksum", 40..48),
+];
+
+fn on_off(value: bool) -> String {
+    if value { "on" } else { "off" }.to_string()
+}
+
+fn timer(value: u64) -> String {
+    if value == 0xff {
+        "disabled".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+impl EliosState {
+    /// The name and bit range of every field in the frame, 0 being the first bit sent.
+    pub fn field_layout() -> &'static [(&'static str, Range<usize>)] {
+        &FIELD_LAYOUT
+    }
+
+    /// A markdown table breaking the frame down into its fields, with the bit range, raw value
+    /// and meaning of each.
+    pub fn to_markdown_table(self) -> String {
+        let value = self.as_value();
+        let mut table = String::from("| Field | Bits | Raw | Meaning |\n|---|---|---|---|\n");
+
+        for (name, bits) in Self::field_layout() {
+            let width = bits.end - bits.start;
+            let raw = value >> (Self::FRAME_BITS - bits.end) & ((1 << width) - 1);
+
+            let meaning = match *name {
+                "Header" => "Elios".to_string(),
+                "Power" => on_off(self.powered()),
+                "Sleep" => on_off(self.sleep()),
+                "Fan speed" => fan_speed_name(self.fan_speed()).to_string(),
+                "Mode" => mode_name(self.mode()).to_string(),
+                "Unit" => match self.temperature() {
+                    Some(Temperature::Fahrenheit(_)) => "fahrenheit".to_string(),
+                    _ => "celcius".to_string(),
+                },
+                "Temperature" => match self.temperature() {
+                    Some(Temperature::Celcius(temp)) | Some(Temperature::Fahrenheit(temp)) => {
+                        temp.to_string()
+                    }
+                    None => "fan mode sentinel".to_string(),
+                },
+                "Off timer" | "On timer" => timer(raw),
+                _ => String::new(),
+            };
+
+            table.push_str(&format!(
+                "| {} | {}-{} | {:0width$b} | {} |\n",
+                name,
+                bits.start,
+                bits.end - 1,
+                raw,
+                meaning,
+                width = width
+            ));
+        }
+
+        table
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn when_field_layout_then_covers_the_whole_frame() {
+        let layout = EliosState::field_layout();
+
+        assert_eq!(layout[0].1.start, 0);
+        assert_eq!(layout[layout.len() - 1].1.end, EliosState::FRAME_BITS);
+        assert!(layout
+            .windows(2)
+            .all(|pair| pair[0].1.end == pair[1].1.start));
+    }
+
+    #[test]
+    fn given_state_then_markdown_table_has_a_row_per_field() {
+        let table = EliosState::cool(Temperature::Celcius(22)).to_markdown_table();
+
+        assert_eq!(table.lines().count(), 2 + 11);
+        assert!(table.contains("| Temperature | 19-23 | 00101 | 22 |"));
+        assert!(table.contains("| Mode | 13-15 | 000 | cold |"));
+        assert!(table.contains("| Off timer | 24-31 | 11111111 | disabled |"));
+    }
+}
@@ -0,0 +1,90 @@
+/// Shorthand for `EliosState::new`, evaluating to an `Option<EliosState>`:
+///
+/// ```
+/// use acproto::state;
+///
+/// let cooling = state!(cold, 22 C, on, fan: low);
+/// let heating = state!(heat, 70 F, on, sleep: on);
+/// let venting = state!(fan, off, fan: high);
+/// ```
+///
+/// Modes are `cold`, `dry`, `auto`, `heat` and `fan`, the latter taking no temperature. Fan
+/// speeds are `off`, `auto`, `low`, `medium` and `high`, left to `new` when omitted.
+#[macro_export]
+macro_rules! state {
+    (fan, $power:ident $(, fan: $fan:ident)? $(, sleep: $sleep:ident)?) => {
+        $crate::elios::EliosState::new(
+            None $(.or(Some($crate::state!(@fan $fan))))?,
+            $crate::elios::EliosMode::Fan,
+            None,
+            $crate::state!(@switch $power),
+            false $(|| $crate::state!(@switch $sleep))?,
+        )
+    };
+    ($mode:ident, $temp:literal $unit:ident, $power:ident
+        $(, fan: $fan:ident)? $(, sleep: $sleep:ident)?) => {
+        $crate::elios::EliosState::new(
+            None $(.or(Some($crate::state!(@fan $fan))))?,
+            $crate::state!(@mode $mode),
+            Some($crate::state!(@temperature $temp $unit)),
+            $crate::state!(@switch $power),
+            false $(|| $crate::state!(@switch $sleep))?,
+        )
+    };
+
+    (@mode cold) => { $crate::elios::EliosMode::Cold };
+    (@mode dry) => { $crate::elios::EliosMode::Dry };
+    (@mode auto) => { $crate::elios::EliosMode::Automatic };
+    (@mode heat) => { $crate::elios::EliosMode::Heat };
+
+    (@fan off) => { $crate::elios::EliosFanSpeed::Off };
+    (@fan auto) => { $crate::elios::EliosFanSpeed::Automatic };
+    (@fan low) => { $crate::elios::EliosFanSpeed::Low };
+    (@fan medium) => { $crate::elios::EliosFanSpeed::Medium };
+    (@fan high) => { $crate::elios::EliosFanSpeed::High };
+
+    (@temperature $temp:literal C) => { $crate::common::Temperature::Celcius($temp) };
+    (@temperature $temp:literal F) => { $crate::common::Temperature::Fahrenheit($temp) };
+
+    (@switch on) => { true };
+    (@switch off) => { false };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::Temperature;
+    use crate::elios::{EliosFanSpeed as FanSpeed, EliosMode as Mode, EliosState};
+
+    #[test]
+    fn given_macro_then_state_matches_constructor() {
+        assert_eq!(
+            crate::state!(cold, 22 C, on, fan: low),
+            EliosState::new(
+                Some(FanSpeed::Low),
+                Mode::Cold,
+                Some(Temperature::Celcius(22)),
+                true,
+                false
+            )
+        );
+        assert_eq!(
+            crate::state!(heat, 70 F, off, sleep: on),
+            EliosState::new(
+                None,
+                Mode::Heat,
+                Some(Temperature::Fahrenheit(70)),
+                false,
+                true
+            )
+        );
+        assert_eq!(
+            crate::state!(fan, on, fan: high),
+            EliosState::new(Some(FanSpeed::High), Mode::Fan, None, true, false)
+        );
+    }
+
+    #[test]
+    fn given_macro_with_invalid_settings_then_it_is_rejected() {
+        assert_eq!(crate::state!(dry, 22 C, on, fan: high), None);
+    }
+}
@@ -1,7 +1,32 @@
+mod builder;
+mod button;
+mod capture;
+mod compact;
+mod controller;
+mod csv;
+mod decoded;
+mod describe;
 mod fan;
+mod intent;
+mod json;
+mod layout;
+mod macros;
 mod mode;
+mod self_test;
+mod share;
 mod state;
 
+pub use self::builder::{Adjustment, BuildReport, EliosStateBuilder};
+pub use self::button::{keymap, RemoteButton};
+pub use self::capture::{dedupe_frames, frame_hamming_distance, histogram, reserved_bit_values};
+pub use self::controller::EliosController;
+pub use self::csv::parse_and_validate_csv;
+pub use self::decoded::DecodedFrame;
 pub use self::fan::EliosFanSpeed;
+pub use self::intent::{sync_frames, Intent};
 pub use self::mode::EliosMode;
-pub use self::state::{EliosState, ELIOS_IR};
+pub use self::self_test::{roundtrip, self_test};
+pub use self::state::{
+    EliosState, Overrides, StateError, TemperatureOffsetBase, ELIOS_IR, ELIOS_IR_56K, FAN_SHIFT,
+    POWER_BIT, SLEEP_BIT, UNIT_FLAG_BIT,
+};
@@ -1,4 +1,4 @@
-#[derive(Debug, PartialEq, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
 pub enum EliosMode {
     Cold = 0b000,
     Dry = 0b001,
@@ -6,3 +6,59 @@ pub enum EliosMode {
     Heat = 0b011,
     Fan = 0b100,
 }
+
+impl EliosMode {
+    pub const ALL: [Self; 5] = [
+        EliosMode::Cold,
+        EliosMode::Dry,
+        EliosMode::Automatic,
+        EliosMode::Heat,
+        EliosMode::Fan,
+    ];
+
+    pub fn as_bits(self) -> u8 {
+        self as u8
+    }
+
+    /// Whether the unit honors the sleep setting in this mode.
+    pub fn supports_sleep(self) -> bool {
+        match self {
+            EliosMode::Cold | EliosMode::Heat | EliosMode::Automatic => true,
+            EliosMode::Dry | EliosMode::Fan => false,
+        }
+    }
+
+    pub fn from_bits(bits: u8) -> Option<Self> {
+        match bits {
+            0b000 => Some(EliosMode::Cold),
+            0b001 => Some(EliosMode::Dry),
+            0b010 => Some(EliosMode::Automatic),
+            0b011 => Some(EliosMode::Heat),
+            0b100 => Some(EliosMode::Fan),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn when_as_bits_then_protocol_code_is_returned() {
+        assert_eq!(EliosMode::Cold.as_bits(), 0b000);
+        assert_eq!(EliosMode::Dry.as_bits(), 0b001);
+        assert_eq!(EliosMode::Automatic.as_bits(), 0b010);
+        assert_eq!(EliosMode::Heat.as_bits(), 0b011);
+        assert_eq!(EliosMode::Fan.as_bits(), 0b100);
+    }
+
+    #[test]
+    fn given_bits_then_variant_is_recovered() {
+        for bits in 0..8 {
+            if let Some(value) = EliosMode::from_bits(bits) {
+                assert_eq!(value.as_bits(), bits);
+            }
+        }
+    }
+}
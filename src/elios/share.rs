@@ -0,0 +1,99 @@
+use crate::elios::EliosState;
+
+/// Crockford base32 digits, followed by the five extra check symbols.
+const ALPHABET: &[u8; 37] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ*~$=U";
+
+/// Data characters in a share code, covering the 16 compact bits padded to 20.
+const DATA_CHARS: usize = 4;
+
+fn symbol_value(symbol: char) -> Option<u8> {
+    // Crockford decoding is case insensitive and reads the ambiguous letters as digits
+    let symbol = match symbol.to_ascii_uppercase() {
+        'O' => '0',
+        'I' | 'L' => '1',
+        other => other,
+    };
+
+    ALPHABET
+        .iter()
+        .position(|&candidate| candidate as char == symbol)
+        .map(|position| position as u8)
+}
+
+impl EliosState {
+    /// A short human-transcribable code for the state: the compact bytes in Crockford base32,
+    /// followed by a Crockford check symbol.
+    pub fn to_share_code(self) -> String {
+        let value = u16::from_be_bytes(self.to_compact()) as u32;
+        let padded = value << 4;
+
+        let mut code: String = (0..DATA_CHARS)
+            .rev()
+            .map(|index| ALPHABET[(padded >> (5 * index) & 0b11111) as usize] as char)
+            .collect();
+        code.push(ALPHABET[(value % 37) as usize] as char);
+
+        code
+    }
+
+    /// Parses a code made by `to_share_code`, `None` if it is malformed, its check symbol does
+    /// not match, or it does not hold a valid state.
+    pub fn from_share_code(code: &str) -> Option<Self> {
+        let symbols = code
+            .chars()
+            .map(symbol_value)
+            .collect::<Option<Vec<u8>>>()?;
+
+        if symbols.len() != DATA_CHARS + 1 {
+            return None;
+        }
+
+        let (check, data) = symbols.split_last()?;
+        let mut padded = 0u32;
+
+        for symbol in data {
+            if *symbol >= 32 {
+                return None;
+            }
+
+            padded = padded << 5 | *symbol as u32;
+        }
+
+        if padded & 0b1111 != 0 {
+            return None;
+        }
+
+        let value = padded >> 4;
+
+        if value % 37 != *check as u32 {
+            return None;
+        }
+
+        Self::from_compact((value as u16).to_be_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::Temperature;
+
+    #[test]
+    fn given_every_valid_state_then_share_code_round_trips() {
+        for state in EliosState::all_valid() {
+            let code = state.to_share_code();
+
+            assert_eq!(code.len(), 5);
+            assert_eq!(EliosState::from_share_code(&code), Some(state));
+        }
+    }
+
+    #[test]
+    fn given_share_code_with_wrong_check_symbol_then_it_is_rejected() {
+        let mut code = EliosState::cool(Temperature::Celcius(22)).to_share_code();
+        let check = code.pop().unwrap();
+        code.push(if check == '0' { '1' } else { '0' });
+
+        assert_eq!(EliosState::from_share_code(&code), None);
+    }
+}
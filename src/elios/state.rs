@@ -1,14 +1,51 @@
 use bit_vec::BitVec;
+use std::error;
+use std::fmt;
+use std::ops::RangeInclusive;
 
 use crate::common::*;
 use crate::elios::{EliosFanSpeed as FanSpeed, EliosMode as Mode};
 
-const MIN_CELCIUS: u8 = 17;
-const MAX_CELCIUS: u8 = 30;
-const MIN_FAHRENHEIT: u8 = 62;
-const MAX_FAHRENHEIT: u8 = 86;
+pub(crate) const MIN_CELCIUS: u8 = 17;
+pub(crate) const MAX_CELCIUS: u8 = 30;
+pub(crate) const MIN_FAHRENHEIT: u8 = 62;
+pub(crate) const MAX_FAHRENHEIT: u8 = 86;
 
-const FAN_TEMPERATURE: u8 = 0b11110;
+pub(crate) const FAN_TEMPERATURE: u8 = 0b11110;
+
+/// Bit of the options byte (`data[1]`) set when the unit is powered.
+pub const POWER_BIT: u8 = 7;
+/// Bit of the options byte set when sleep is on.
+pub const SLEEP_BIT: u8 = 6;
+/// Lowest bit of the 3-bit fan speed field of the options byte, the mode being below it.
+pub const FAN_SHIFT: u8 = 3;
+/// Bit of the temperature byte (`data[2]`) set when the temperature is in Fahrenheit.
+pub const UNIT_FLAG_BIT: u8 = 5;
+
+/// The two high bits of the temperature byte, of unknown meaning, always sent as 0b01 by the remote
+pub(crate) const RESERVED_BITS: u8 = 0b01;
+
+/// Used when leaving fan mode, whose frame carries no setpoint.
+pub(crate) const DEFAULT_TEMPERATURE: Temperature = Temperature::Celcius(24);
+
+/// Moves `temperature` by `delta` degrees in its own unit, saturating at the bounds of a `u8`.
+pub(crate) fn step_temperature(temperature: Temperature, delta: i8) -> Temperature {
+    let step = |temp: u8| (temp as i16 + delta as i16).clamp(0, u8::MAX as i16) as u8;
+
+    match temperature {
+        Temperature::Celcius(temp) => Temperature::Celcius(step(temp)),
+        Temperature::Fahrenheit(temp) => Temperature::Fahrenheit(step(temp)),
+    }
+}
+
+/// Converts `value` degrees from one unit to the other, unrounded.
+pub(crate) fn convert_degrees(value: f32, from: TemperatureUnit, to: TemperatureUnit) -> f32 {
+    match (from, to) {
+        (TemperatureUnit::Celcius, TemperatureUnit::Fahrenheit) => value * 9.0 / 5.0 + 32.0,
+        (TemperatureUnit::Fahrenheit, TemperatureUnit::Celcius) => (value - 32.0) * 5.0 / 9.0,
+        _ => value,
+    }
+}
 
 pub const ELIOS_IR: InfraredProtocol = InfraredProtocol {
     leading_pulse: 4350,
@@ -17,9 +54,74 @@ pub const ELIOS_IR: InfraredProtocol = InfraredProtocol {
     one_gap: 1550,
     zero_pulse: 550,
     zero_gap: 550,
+    carrier_hz: 38000,
+    logger: None,
 };
 
-#[derive(Debug, Copy, Clone)]
+/// Elios-compatible clones modulating their pulses at 56kHz instead of 38kHz.
+pub const ELIOS_IR_56K: InfraredProtocol = InfraredProtocol {
+    carrier_hz: 56000,
+    ..ELIOS_IR
+};
+
+/// The requested settings `EliosState::new_lenient` had to override.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct Overrides {
+    /// A fan speed was requested in a mode where the unit manages the fan
+    pub fan_speed: bool,
+    /// Sleep was requested in a mode without a sleep setting
+    pub sleep: bool,
+}
+
+/// How the temperature byte encodes the setpoint. The Elios remote sends an unsigned offset from
+/// the unit's minimum; some clones send a signed offset from 24 degrees Celcius instead.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum TemperatureOffsetBase {
+    From17Unsigned,
+    /// 5-bit two's complement offset from 24. Only seen with Celcius, so Fahrenheit setpoints and
+    /// the fan mode marker keep the regular encoding.
+    From24Signed,
+}
+
+const SIGNED_OFFSET_BASE: i8 = 24;
+
+/// Why a combination of settings does not make a valid state.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum StateError {
+    /// Fan mode has no setpoint, so no temperature can be given
+    TemperatureInFanMode,
+    /// Every mode other than fan needs a temperature
+    MissingTemperature,
+    /// A fan speed was given in a mode where the unit manages the fan
+    FanSpeedInManagedMode(Mode),
+    /// Sleep was toggled in a mode without a sleep setting
+    SleepUnsupported(Mode),
+    /// A textual setting could not be parsed, holding the name of the field
+    InvalidField(&'static str),
+}
+
+impl fmt::Display for StateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StateError::TemperatureInFanMode => write!(f, "fan mode has no temperature"),
+            StateError::MissingTemperature => write!(f, "a temperature is required"),
+            StateError::FanSpeedInManagedMode(mode) => {
+                write!(f, "the unit manages the fan in {:?} mode", mode)
+            }
+            StateError::SleepUnsupported(mode) => write!(f, "{:?} mode has no sleep", mode),
+            StateError::InvalidField(field) => write!(f, "invalid {}", field),
+        }
+    }
+}
+
+impl error::Error for StateError {}
+
+/// A complete remote command. The protocol has no separate "power on and resume" command: every
+/// frame, powered or not, carries the full set of settings and the unit applies all of them.
+///
+/// The protocol is one-way: there is no query frame, so the unit cannot be asked to display or
+/// report its sensor reading.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct EliosState {
     fan_speed: FanSpeed,
     mode: Mode,
@@ -36,31 +138,38 @@ impl EliosState {
         powered: bool,
         sleep: bool,
     ) -> Option<Self> {
+        Self::try_new(fan_speed, mode, temperature, powered, sleep).ok()
+    }
+
+    /// Same as `new`, telling why the settings were rejected.
+    pub fn try_new(
+        fan_speed: Option<FanSpeed>,
+        mode: Mode,
+        temperature: Option<Temperature>,
+        powered: bool,
+        sleep: bool,
+    ) -> Result<Self, StateError> {
         let temperature = if mode == Mode::Fan {
             if temperature.is_some() {
-                return None;
+                return Err(StateError::TemperatureInFanMode);
             }
 
             Temperature::Celcius(MIN_CELCIUS + FAN_TEMPERATURE)
         } else {
-            if temperature.is_none() {
-                return None;
-            }
-
-            match temperature.unwrap() {
+            match temperature.ok_or(StateError::MissingTemperature)? {
                 Temperature::Celcius(temp) => {
-                    Temperature::Celcius(temp.min(MAX_CELCIUS).max(MIN_CELCIUS))
+                    Temperature::Celcius(temp.clamp(MIN_CELCIUS, MAX_CELCIUS))
                 }
                 Temperature::Fahrenheit(temp) => {
-                    Temperature::Fahrenheit(temp.min(MAX_FAHRENHEIT).max(MIN_FAHRENHEIT))
+                    Temperature::Fahrenheit(temp.clamp(MIN_FAHRENHEIT, MAX_FAHRENHEIT))
                 }
             }
         };
 
         let fan = match mode {
             Mode::Automatic | Mode::Dry => {
-                if fan_speed.is_some() && fan_speed.unwrap() != FanSpeed::Off {
-                    return None;
+                if fan_speed.is_some_and(|fan| fan != FanSpeed::Off) {
+                    return Err(StateError::FanSpeedInManagedMode(mode));
                 }
 
                 FanSpeed::Off
@@ -68,9 +177,9 @@ impl EliosState {
             _ => fan_speed.unwrap_or(FanSpeed::Automatic),
         };
 
-        let sleep = sleep && (mode == Mode::Cold || mode == Mode::Heat || mode == Mode::Automatic);
+        let sleep = sleep && mode.supports_sleep();
 
-        Some(Self {
+        Ok(Self {
             fan_speed: fan,
             mode,
             temperature,
@@ -79,35 +188,302 @@ impl EliosState {
         })
     }
 
+    /// Same as `new`, except that a fan speed selected in automatic or dry mode is forced to off
+    /// instead of being rejected. The returned `Overrides` tell which requested settings, including
+    /// a sleep request `new` silently drops, did not make it into the state.
+    pub fn new_lenient(
+        fan_speed: Option<FanSpeed>,
+        mode: Mode,
+        temperature: Option<Temperature>,
+        powered: bool,
+        sleep: bool,
+    ) -> Option<(Self, Overrides)> {
+        let overrides = Overrides {
+            fan_speed: match mode {
+                Mode::Automatic | Mode::Dry => fan_speed.is_some_and(|fan| fan != FanSpeed::Off),
+                _ => false,
+            },
+            sleep: sleep && !mode.supports_sleep(),
+        };
+        let fan_speed = if overrides.fan_speed { None } else { fan_speed };
+
+        Self::new(fan_speed, mode, temperature, powered, sleep).map(|state| (state, overrides))
+    }
+
+    // the shorthands below only pass arguments `new` accepts, so unwrapping cannot fail
+
+    /// Powered cooling with automatic fan.
+    pub fn cool(temperature: Temperature) -> Self {
+        Self::new(None, Mode::Cold, Some(temperature), true, false).unwrap()
+    }
+
+    /// Powered heating with automatic fan.
+    pub fn heat(temperature: Temperature) -> Self {
+        Self::new(None, Mode::Heat, Some(temperature), true, false).unwrap()
+    }
+
+    /// Powered dehumidifying, the unit manages the fan.
+    pub fn dry(temperature: Temperature) -> Self {
+        Self::new(None, Mode::Dry, Some(temperature), true, false).unwrap()
+    }
+
+    /// Powered ventilation only with automatic fan.
+    pub fn fan_only() -> Self {
+        Self::new(None, Mode::Fan, None, true, false).unwrap()
+    }
+
+    /// Powered automatic mode, the unit manages the fan.
+    pub fn auto(temperature: Temperature) -> Self {
+        Self::new(None, Mode::Automatic, Some(temperature), true, false).unwrap()
+    }
+
+    /// Every distinct state `new` can produce.
+    pub fn all_valid() -> impl Iterator<Item = EliosState> {
+        let mut states = Vec::new();
+
+        for &mode in Mode::ALL.iter() {
+            let fan_speeds = match mode {
+                Mode::Automatic | Mode::Dry => vec![FanSpeed::Off],
+                _ => FanSpeed::ALL.to_vec(),
+            };
+
+            let temperatures: Vec<Option<Temperature>> = if mode == Mode::Fan {
+                vec![None]
+            } else {
+                Self::temperature_range(TemperatureUnit::Celcius)
+                    .map(Temperature::Celcius)
+                    .chain(
+                        Self::temperature_range(TemperatureUnit::Fahrenheit)
+                            .map(Temperature::Fahrenheit),
+                    )
+                    .map(Some)
+                    .collect()
+            };
+
+            let sleeps = if mode.supports_sleep() {
+                vec![false, true]
+            } else {
+                vec![false]
+            };
+
+            for &fan_speed in fan_speeds.iter() {
+                for &temperature in temperatures.iter() {
+                    for &powered in [false, true].iter() {
+                        for &sleep in sleeps.iter() {
+                            states.extend(Self::new(
+                                Some(fan_speed),
+                                mode,
+                                temperature,
+                                powered,
+                                sleep,
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+
+        states.into_iter()
+    }
+
+    /// The setpoints the unit accepts in the given unit.
+    pub fn temperature_range(unit: TemperatureUnit) -> RangeInclusive<u8> {
+        match unit {
+            TemperatureUnit::Celcius => MIN_CELCIUS..=MAX_CELCIUS,
+            TemperatureUnit::Fahrenheit => MIN_FAHRENHEIT..=MAX_FAHRENHEIT,
+        }
+    }
+
+    /// One state per setpoint in the unit of `base`, keeping its other settings. Empty in fan
+    /// mode, which has no setpoint.
+    pub fn temperature_sweep(base: EliosState) -> Vec<EliosState> {
+        let unit = match base.temperature() {
+            Some(temperature) => temperature.unit(),
+            None => return Vec::new(),
+        };
+
+        Self::temperature_range(unit)
+            .filter_map(|temp| {
+                let temperature = match unit {
+                    TemperatureUnit::Celcius => Temperature::Celcius(temp),
+                    TemperatureUnit::Fahrenheit => Temperature::Fahrenheit(temp),
+                };
+
+                Self::new(
+                    Some(base.fan_speed),
+                    base.mode,
+                    Some(temperature),
+                    base.powered,
+                    base.sleep,
+                )
+            })
+            .collect()
+    }
+
+    /// The same state with its setpoint converted to the other unit, rounded to the nearest
+    /// degree. Fan mode has no setpoint and is returned as is.
+    pub fn toggle_unit(self) -> EliosState {
+        let temperature = match self.temperature() {
+            Some(Temperature::Celcius(temp)) => Temperature::snap(
+                convert_degrees(
+                    temp as f32,
+                    TemperatureUnit::Celcius,
+                    TemperatureUnit::Fahrenheit,
+                ),
+                TemperatureUnit::Fahrenheit,
+            ),
+            Some(Temperature::Fahrenheit(temp)) => Temperature::snap(
+                convert_degrees(
+                    temp as f32,
+                    TemperatureUnit::Fahrenheit,
+                    TemperatureUnit::Celcius,
+                ),
+                TemperatureUnit::Celcius,
+            ),
+            None => return self,
+        };
+
+        EliosState {
+            temperature,
+            ..self
+        }
+    }
+
+    /// The setpoint in `unit` physically closest to `target_c` degrees Celcius.
+    pub fn closest_step_to_celsius(target_c: f32, unit: TemperatureUnit) -> Temperature {
+        let celcius = |temp: u8| convert_degrees(temp as f32, unit, TemperatureUnit::Celcius);
+
+        let range = Self::temperature_range(unit);
+        let temp = range
+            .clone()
+            .min_by(|a, b| {
+                let a = (celcius(*a) - target_c).abs();
+                let b = (celcius(*b) - target_c).abs();
+                a.partial_cmp(&b).unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .unwrap_or(*range.start());
+
+        match unit {
+            TemperatureUnit::Celcius => Temperature::Celcius(temp),
+            TemperatureUnit::Fahrenheit => Temperature::Fahrenheit(temp),
+        }
+    }
+
+    /// The number of distinct setpoints in the given unit, e.g. for a stepper control.
+    pub fn temperature_step_count(unit: TemperatureUnit) -> usize {
+        Self::temperature_range(unit).count()
+    }
+
+    pub fn fan_speed(&self) -> FanSpeed {
+        self.fan_speed
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// The temperature setting, `None` in fan mode where the frame carries a sentinel value.
+    pub fn temperature(&self) -> Option<Temperature> {
+        if self.mode == Mode::Fan {
+            None
+        } else {
+            Some(self.temperature)
+        }
+    }
+
+    pub fn powered(&self) -> bool {
+        self.powered
+    }
+
+    pub fn sleep(&self) -> bool {
+        self.sleep
+    }
+
+    /// Decodes a 48-bit frame produced by `as_value`. Returns `None` when the frame is not one
+    /// that `new` could have produced, including a bad header or checksum.
+    pub fn from_value(value: u64) -> Option<Self> {
+        if value >> 48 != 0 {
+            return None;
+        }
+
+        let options = (value >> 32) as u8;
+        let temperature = (value >> 24) as u8;
+
+        let mode = Mode::from_bits(options & 0b111)?;
+        let fan_speed = FanSpeed::from_bits(options >> FAN_SHIFT & 0b111)?;
+
+        let temperature = if mode == Mode::Fan {
+            None
+        } else if temperature >> UNIT_FLAG_BIT & 1 == 1 {
+            Some(Temperature::Fahrenheit(
+                (temperature & 0b11111) + MIN_FAHRENHEIT,
+            ))
+        } else {
+            Some(Temperature::Celcius((temperature & 0b11111) + MIN_CELCIUS))
+        };
+
+        let state = Self::new(
+            Some(fan_speed),
+            mode,
+            temperature,
+            options >> POWER_BIT & 1 == 1,
+            options >> SLEEP_BIT & 1 == 1,
+        )?;
+
+        // re-encoding validates the header, checksum and every constant bit at once
+        if state.as_value() == value {
+            Some(state)
+        } else {
+            None
+        }
+    }
+
     fn as_raw_parts(self) -> [u8; 5] {
+        self.as_raw_parts_with_offset_base(TemperatureOffsetBase::From17Unsigned)
+    }
+
+    fn as_raw_parts_with_offset_base(self, base: TemperatureOffsetBase) -> [u8; 5] {
         let mut data: [u8; 5] = [0; 5];
 
         // header
         data[0] = 0b10100001;
 
         // options
-        data[1] = (self.powered as u8) << 7
-            | (self.sleep as u8) << 6
-            | (self.fan_speed as u8) << 3
-            | (self.mode as u8);
-
-        // temperature
-        data[2] = 1 << 6 // unknown 2 bit value
-            | (match self.temperature {
-                Temperature::Celcius(temp) => temp - MIN_CELCIUS,
-                Temperature::Fahrenheit(temp) => temp - MIN_FAHRENHEIT | 0b1 << 5,
-            } as u8);
-
-        // timer off
+        data[1] = (self.powered as u8) << POWER_BIT
+            | (self.sleep as u8) << SLEEP_BIT
+            | self.fan_speed.as_bits() << FAN_SHIFT
+            | self.mode.as_bits();
+
+        // temperature, as an offset from the unit's minimum in the low 5 bits:
+        //   Celcius     17..=30 -> 0..=13
+        //   Fahrenheit  62..=86 -> 0..=24, with the unit flag at bit 5
+        // each unit has its own codes, Fahrenheit is not converted to the Celcius ones
+        data[2] = RESERVED_BITS << 6
+            | match self.temperature {
+                Temperature::Celcius(temp)
+                    if base == TemperatureOffsetBase::From24Signed && self.mode != Mode::Fan =>
+                {
+                    (temp as i8 - SIGNED_OFFSET_BASE) as u8 & 0b11111
+                }
+                Temperature::Celcius(temp) => temp.saturating_sub(MIN_CELCIUS),
+                Temperature::Fahrenheit(temp) => {
+                    temp.saturating_sub(MIN_FAHRENHEIT) | 0b1 << UNIT_FLAG_BIT
+                }
+            };
+
+        // timer off, 0xff meaning disabled. No capture with a timer set is known yet, so the
+        // duration encoding is unknown and programming a timer is not supported
         data[3] = 0b11111111;
 
-        // timer on
+        // timer on, disabled like the off timer and for the same reason
         data[4] = 0b11111111;
 
         data
     }
 
-    fn checksum(data: &[u8; 5]) -> u8 {
+    /// Only the header, options and temperature bytes are covered: the timer bytes of every
+    /// captured frame are 0xff, so whether the unit validates them once set is unknown.
+    pub(crate) fn checksum(data: &[u8; 5]) -> u8 {
         let data: Vec<u8> = data.iter().map(bitreverse).collect();
 
         let xor_nibble = (data[0]
@@ -124,28 +500,126 @@ impl EliosState {
         bitreverse(&value)
     }
 
+    /// The powered on and powered off frames for the same settings.
+    pub fn powered_variants(self) -> (u64, u64) {
+        let on = EliosState {
+            powered: true,
+            ..self
+        };
+        let off = EliosState {
+            powered: false,
+            ..self
+        };
+
+        (on.as_value(), off.as_value())
+    }
+
+    /// The command that undoes this one when it was sent over `previous`. Since every frame
+    /// carries the full state, this is `previous` itself.
+    pub fn inverse(self, previous: EliosState) -> EliosState {
+        previous
+    }
+
+    /// Advisory check of whether the physical remote can produce this state. The remote's fan
+    /// button only cycles through automatic, low, medium and high, so an off fan is only sent in
+    /// the modes where the unit manages the fan, even though `new` accepts it in any mode.
+    pub fn is_remote_reachable(self) -> bool {
+        match self.mode {
+            Mode::Automatic | Mode::Dry => true,
+            _ => self.fan_speed != FanSpeed::Off,
+        }
+    }
+
+    /// A CRC-8 over the five data bytes, independent of the protocol checksum, to cross-check
+    /// captures for corruption the checksum misses.
+    pub fn crc8(self) -> u8 {
+        crc8(&self.as_raw_parts())
+    }
+
+    /// The frame bit positions (0 being the first bit sent) whose single flip leaves the
+    /// checksum unchanged, so the receiver cannot detect the error.
+    pub fn undetectable_error_positions(self) -> Vec<usize> {
+        let data = self.as_raw_parts();
+        let checksum = EliosState::checksum(&data);
+
+        (0..data.len() * 8)
+            .filter(|position| {
+                let mut flipped = data;
+                flipped[position / 8] ^= 0b10000000 >> (position % 8);
+
+                EliosState::checksum(&flipped) == checksum
+            })
+            .collect()
+    }
+
     pub fn as_value(self) -> u64 {
+        let checksum = EliosState::checksum(&self.as_raw_parts());
+
+        self.as_value_with_checksum(checksum)
+    }
+
+    /// Encodes the state with a caller-supplied checksum instead of the computed one, for
+    /// fault-injection testing of receivers.
+    pub fn as_value_with_checksum(self, checksum: u8) -> u64 {
         let data = self.as_raw_parts();
+
+        data.iter().fold(0, |acc, x| acc << 8 | *x as u64) << 8 | checksum as u64
+    }
+
+    /// Same as `as_value`, encoding the temperature with the given offset base for clones that
+    /// do not use the Elios one.
+    pub fn as_value_with_offset_base(self, base: TemperatureOffsetBase) -> u64 {
+        let data = self.as_raw_parts_with_offset_base(base);
         let checksum = EliosState::checksum(&data);
 
         data.iter().fold(0, |acc, x| acc << 8 | *x as u64) << 8 | checksum as u64
     }
-}
 
-impl AsBitVec for EliosState {
-    fn as_bitvec(self) -> BitVec {
+    /// The six frame bytes in transmission order, checksum last.
+    pub fn as_bytes(self) -> [u8; 6] {
         let data = self.as_raw_parts();
         let checksum = EliosState::checksum(&data);
 
-        let mut buffer = data.to_vec();
-        buffer.push(checksum);
+        [data[0], data[1], data[2], data[3], data[4], checksum]
+    }
+
+    /// The frame packed into three big-endian 16-bit words.
+    pub fn as_words(self) -> [u16; 3] {
+        let bytes = self.as_bytes();
 
-        let bits = BitVec::from_bytes(&buffer.as_slice());
-        bits
+        [
+            u16::from_be_bytes([bytes[0], bytes[1]]),
+            u16::from_be_bytes([bytes[2], bytes[3]]),
+            u16::from_be_bytes([bytes[4], bytes[5]]),
+        ]
+    }
+
+    /// The first four frame bytes labeled as an NEC `(address, address_inverse, command,
+    /// command_inverse)` tuple. Elios is not NEC and the "inverse" bytes are not complements; this
+    /// only exists to line frames up with analyzers that assume that layout.
+    pub fn as_nec_view(self) -> (u8, u8, u8, u8) {
+        let bytes = self.as_bytes();
+
+        (bytes[0], bytes[1], bytes[2], bytes[3])
+    }
+}
+
+impl ClimateState for EliosState {
+    const FRAME_BITS: usize = 48;
+
+    fn manufacturer() -> &'static str {
+        "Elios"
+    }
+}
+
+impl AsBitVec for EliosState {
+    fn as_bitvec(self) -> BitVec {
+        BitVec::from_bytes(&self.as_bytes())
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
 
@@ -313,6 +787,394 @@ mod tests {
         );
     }
 
+    #[test]
+    fn given_every_expected_value_then_from_value_rebuilds_the_state() {
+        let new = |fan_speed, mode, temperature, powered, sleep| {
+            EliosState::new(fan_speed, mode, temperature, powered, sleep).unwrap()
+        };
+        let auto = Some(FanSpeed::Automatic);
+
+        let expectations = [
+            (
+                new(
+                    auto,
+                    Mode::Cold,
+                    Some(Temperature::Celcius(17)),
+                    true,
+                    false,
+                ),
+                0b10100001_10100000_01000000_11111111_11111111_01101110,
+            ),
+            (
+                new(
+                    auto,
+                    Mode::Cold,
+                    Some(Temperature::Celcius(18)),
+                    true,
+                    false,
+                ),
+                0b10100001_10100000_01000001_11111111_11111111_01101111,
+            ),
+            (
+                new(
+                    auto,
+                    Mode::Cold,
+                    Some(Temperature::Fahrenheit(62)),
+                    true,
+                    false,
+                ),
+                0b10100001_10100000_01100000_11111111_11111111_01001110,
+            ),
+            (
+                new(
+                    auto,
+                    Mode::Cold,
+                    Some(Temperature::Celcius(17)),
+                    false,
+                    false,
+                ),
+                0b10100001_00100000_01000000_11111111_11111111_11101110,
+            ),
+            (
+                new(auto, Mode::Cold, Some(Temperature::Celcius(17)), true, true),
+                0b10100001_11100000_01000000_11111111_11111111_00101110,
+            ),
+            (
+                new(
+                    auto,
+                    Mode::Heat,
+                    Some(Temperature::Celcius(30)),
+                    true,
+                    false,
+                ),
+                0b10100001_10100011_01001101_11111111_11111111_01100000,
+            ),
+            (
+                new(auto, Mode::Fan, None, true, false),
+                0b10100001_10100100_01011110_11111111_11111111_01111011,
+            ),
+            (
+                new(None, Mode::Dry, Some(Temperature::Celcius(30)), true, false),
+                0b10100001_10000001_01001101_11111111_11111111_01010010,
+            ),
+            (
+                new(
+                    auto,
+                    Mode::Cold,
+                    Some(Temperature::Fahrenheit(78)),
+                    true,
+                    false,
+                ),
+                0b10100001_10100000_01110000_11111111_11111111_01010110,
+            ),
+            (
+                new(
+                    auto,
+                    Mode::Cold,
+                    Some(Temperature::Fahrenheit(84)),
+                    true,
+                    false,
+                ),
+                0b10100001_10100000_01110110_11111111_11111111_01010000,
+            ),
+            (
+                new(
+                    None,
+                    Mode::Automatic,
+                    Some(Temperature::Celcius(30)),
+                    true,
+                    false,
+                ),
+                0b10100001_10000010_01001101_11111111_11111111_01010001,
+            ),
+        ];
+
+        for (state, value) in expectations.iter() {
+            assert_eq!(EliosState::from_value(*value), Some(*state));
+            assert_eq!(state.as_value(), *value);
+        }
+    }
+
+    #[test]
+    fn given_supplied_checksum_then_value_ends_with_it() {
+        let state = EliosState::new(
+            Some(FanSpeed::Automatic),
+            Mode::Cold,
+            Some(Temperature::Celcius(17)),
+            true,
+            false,
+        )
+        .unwrap();
+
+        let value = state.as_value_with_checksum(0b10101010);
+
+        assert_eq!(value & 0xff, 0b10101010);
+        assert_eq!(value >> 8, state.as_value() >> 8);
+    }
+
+    #[test]
+    fn given_encoded_state_then_value_is_decoded_back() {
+        let state = EliosState::new(
+            Some(FanSpeed::Low),
+            Mode::Heat,
+            Some(Temperature::Fahrenheit(78)),
+            true,
+            true,
+        )
+        .unwrap();
+
+        assert_eq!(EliosState::from_value(state.as_value()), Some(state));
+    }
+
+    #[test]
+    fn given_fan_mode_value_then_sentinel_temperature_is_decoded_back() {
+        let state = EliosState::new(Some(FanSpeed::High), Mode::Fan, None, true, false).unwrap();
+
+        assert_eq!(EliosState::from_value(state.as_value()), Some(state));
+    }
+
+    #[test]
+    fn given_bad_checksum_then_value_is_not_decoded() {
+        let value = 0b10100001_10100000_01000000_11111111_11111111_01101111;
+
+        assert_eq!(EliosState::from_value(value), None);
+    }
+
+    #[test]
+    fn given_bad_header_then_value_is_not_decoded() {
+        let value = 0b10100000_10100000_01000000_11111111_11111111_01101110;
+
+        assert_eq!(EliosState::from_value(value), None);
+    }
+
+    #[test]
+    fn given_state_then_words_reconstruct_value() {
+        let state = EliosState::new(
+            Some(FanSpeed::Medium),
+            Mode::Cold,
+            Some(Temperature::Celcius(22)),
+            true,
+            false,
+        )
+        .unwrap();
+
+        let value = state
+            .as_words()
+            .iter()
+            .fold(0, |acc, x| acc << 16 | *x as u64);
+
+        assert_eq!(value, state.as_value());
+    }
+
+    #[test]
+    fn given_celcius_state_when_unit_toggled_then_nearest_fahrenheit_is_sent() {
+        let state = EliosState::cool(Temperature::Celcius(22)).toggle_unit();
+
+        assert_eq!(state.temperature(), Some(Temperature::Fahrenheit(72)));
+        assert_eq!(EliosState::from_value(state.as_value()), Some(state));
+        assert_eq!(
+            state.toggle_unit().temperature(),
+            Some(Temperature::Celcius(22))
+        );
+    }
+
+    #[test]
+    fn given_elios_protocol_then_carrier_is_38khz() {
+        assert_eq!(ELIOS_IR.carrier_hz(), 38000);
+    }
+
+    #[test]
+    fn given_56khz_clone_then_pronto_frequency_word_reflects_carrier() {
+        let pronto = ELIOS_IR_56K
+            .to_pronto(EliosState::fan_only().as_bitvec())
+            .unwrap();
+
+        assert_eq!(pronto[1], 0x004a);
+    }
+
+    #[test]
+    fn given_elios_protocol_then_bit_threshold_is_between_gaps() {
+        assert_eq!(ELIOS_IR.bit_threshold_us(), 1050);
+    }
+
+    #[test]
+    fn given_signed_offset_base_then_temperature_is_twos_complement_from_24() {
+        let temperature_byte = |temp: u8| {
+            let value = EliosState::cool(Temperature::Celcius(temp))
+                .as_value_with_offset_base(TemperatureOffsetBase::From24Signed);
+
+            (value >> 24) as u8
+        };
+
+        assert_eq!(temperature_byte(20), RESERVED_BITS << 6 | 0b11100);
+        assert_eq!(temperature_byte(28), RESERVED_BITS << 6 | 0b00100);
+    }
+
+    #[test]
+    fn given_unsigned_offset_base_then_frame_matches_as_value() {
+        let state = EliosState::cool(Temperature::Celcius(20));
+
+        assert_eq!(
+            state.as_value_with_offset_base(TemperatureOffsetBase::From17Unsigned),
+            state.as_value()
+        );
+    }
+
+    #[test]
+    fn given_invalid_settings_then_try_new_tells_why() {
+        assert_eq!(
+            EliosState::try_new(None, Mode::Fan, Some(Temperature::Celcius(22)), true, false),
+            Err(StateError::TemperatureInFanMode)
+        );
+        assert_eq!(
+            EliosState::try_new(None, Mode::Cold, None, true, false),
+            Err(StateError::MissingTemperature)
+        );
+        assert_eq!(
+            EliosState::try_new(
+                Some(FanSpeed::Low),
+                Mode::Dry,
+                Some(Temperature::Celcius(22)),
+                true,
+                false
+            ),
+            Err(StateError::FanSpeedInManagedMode(Mode::Dry))
+        );
+    }
+
+    #[test]
+    fn given_fahrenheit_state_then_unit_flag_bit_is_set() {
+        let fahrenheit = EliosState::cool(Temperature::Fahrenheit(70)).as_bytes();
+        let celcius = EliosState::cool(Temperature::Celcius(22)).as_bytes();
+
+        assert_eq!(fahrenheit[2] >> UNIT_FLAG_BIT & 1, 1);
+        assert_eq!(celcius[2] >> UNIT_FLAG_BIT & 1, 0);
+    }
+
+    #[test]
+    fn given_state_then_nec_view_matches_leading_bytes() {
+        let state = EliosState::cool(Temperature::Celcius(22));
+        let bytes = state.as_bytes();
+
+        assert_eq!(
+            state.as_nec_view(),
+            (bytes[0], bytes[1], bytes[2], bytes[3])
+        );
+    }
+
+    #[test]
+    fn given_powered_frame_then_full_settings_are_carried() {
+        let state = EliosState::new(
+            Some(FanSpeed::High),
+            Mode::Heat,
+            Some(Temperature::Celcius(25)),
+            true,
+            true,
+        )
+        .unwrap();
+        let other = EliosState::heat(Temperature::Celcius(25));
+
+        let decoded = EliosState::from_value(state.as_value()).unwrap();
+
+        assert_eq!(decoded.fan_speed, FanSpeed::High);
+        assert_eq!(decoded.mode, Mode::Heat);
+        assert_eq!(decoded.temperature, Temperature::Celcius(25));
+        assert!(decoded.sleep);
+        assert_ne!(state.as_value(), other.as_value());
+    }
+
+    #[test]
+    fn given_known_frame_then_crc8_covers_the_data_bytes() {
+        let state = EliosState::new(
+            Some(FanSpeed::Automatic),
+            Mode::Cold,
+            Some(Temperature::Celcius(17)),
+            true,
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(
+            state.crc8(),
+            crc8(&[0b10100001, 0b10100000, 0b01000000, 0xff, 0xff])
+        );
+        assert_eq!(state.crc8(), 0xcc);
+    }
+
+    #[test]
+    fn given_known_frame_then_undetectable_error_positions_are_reported() {
+        let state = EliosState::new(
+            Some(FanSpeed::Automatic),
+            Mode::Cold,
+            Some(Temperature::Celcius(17)),
+            true,
+            false,
+        )
+        .unwrap();
+
+        let positions = state.undetectable_error_positions();
+
+        // the timer bytes are not covered by the checksum at all
+        assert!((24..40).all(|position| positions.contains(&position)));
+        assert_eq!(positions.len(), 16);
+    }
+
+    #[test]
+    fn given_remote_settings_then_state_is_reachable() {
+        assert!(EliosState::cool(Temperature::Celcius(22)).is_remote_reachable());
+        assert!(EliosState::dry(Temperature::Celcius(22)).is_remote_reachable());
+    }
+
+    #[test]
+    fn given_off_fan_in_cold_mode_then_state_is_encodable_but_unreachable() {
+        let state = EliosState::new(
+            Some(FanSpeed::Off),
+            Mode::Cold,
+            Some(Temperature::Celcius(22)),
+            true,
+            false,
+        )
+        .unwrap();
+
+        assert!(!state.is_remote_reachable());
+    }
+
+    #[test]
+    fn given_state_then_powered_variants_differ_only_in_power_bit_and_checksum() {
+        let (on, off) = EliosState::cool(Temperature::Celcius(22)).powered_variants();
+
+        assert_eq!((on ^ off) & !0xff, 1 << 39);
+        assert_eq!(EliosState::from_value(on).map(|s| s.powered), Some(true));
+        assert_eq!(EliosState::from_value(off).map(|s| s.powered), Some(false));
+    }
+
+    #[test]
+    fn when_inverse_applied_then_original_frame_is_restored() {
+        let original = EliosState::cool(Temperature::Celcius(22));
+        let state = EliosState::heat(Temperature::Celcius(27));
+
+        assert_eq!(state.inverse(original).as_value(), original.as_value());
+    }
+
+    #[test]
+    fn when_frame_bits_then_matches_encoded_frame_length() {
+        fn frame_bits<T: ClimateState>() -> usize {
+            T::frame_bits()
+        }
+
+        assert_eq!(EliosState::FRAME_BITS, 48);
+        assert_eq!(frame_bits::<EliosState>(), 48);
+        assert_eq!(
+            EliosState::fan_only().as_bitvec().len(),
+            EliosState::FRAME_BITS
+        );
+    }
+
+    #[test]
+    fn when_manufacturer_then_is_elios() {
+        assert_eq!(EliosState::manufacturer(), "Elios");
+    }
+
     #[test]
     fn when_auto_mode_then_cannot_select_fan_speed() {
         let state = EliosState::new(
@@ -326,6 +1188,57 @@ mod tests {
         assert!(state.is_none())
     }
 
+    #[test]
+    fn given_lenient_auto_mode_then_fan_speed_is_overridden_to_off() {
+        let (state, overrides) = EliosState::new_lenient(
+            Some(FanSpeed::High),
+            Mode::Automatic,
+            Some(Temperature::Celcius(24)),
+            true,
+            false,
+        )
+        .unwrap();
+
+        assert!(overrides.fan_speed);
+        assert_eq!(state.fan_speed, FanSpeed::Off);
+    }
+
+    #[test]
+    fn given_lenient_cold_mode_then_fan_speed_is_kept() {
+        let (state, overrides) = EliosState::new_lenient(
+            Some(FanSpeed::High),
+            Mode::Cold,
+            Some(Temperature::Celcius(24)),
+            true,
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(overrides, Overrides::default());
+        assert_eq!(state.fan_speed, FanSpeed::High);
+    }
+
+    #[test]
+    fn given_lenient_dry_mode_without_fan_speed_then_nothing_is_overridden() {
+        let (state, overrides) =
+            EliosState::new_lenient(None, Mode::Dry, Some(Temperature::Celcius(24)), true, false)
+                .unwrap();
+
+        assert_eq!(overrides, Overrides::default());
+        assert_eq!(state.fan_speed, FanSpeed::Off);
+    }
+
+    #[test]
+    fn given_lenient_dry_mode_with_sleep_then_sleep_is_reported_as_dropped() {
+        let (state, overrides) =
+            EliosState::new_lenient(None, Mode::Dry, Some(Temperature::Celcius(24)), true, true)
+                .unwrap();
+
+        assert!(overrides.sleep);
+        assert!(!overrides.fan_speed);
+        assert!(!state.sleep);
+    }
+
     #[test]
     fn when_fan_mode_then_cannot_select_temperature() {
         let state = EliosState::new(
@@ -354,6 +1267,144 @@ mod tests {
         assert_eq!(state.sleep, false);
     }
 
+    #[test]
+    fn given_unclamped_sub_minimum_temperature_then_encoding_does_not_underflow() {
+        let celcius = EliosState {
+            fan_speed: FanSpeed::Automatic,
+            mode: Mode::Cold,
+            temperature: Temperature::Celcius(0),
+            powered: true,
+            sleep: false,
+        };
+        let fahrenheit = EliosState {
+            temperature: Temperature::Fahrenheit(0),
+            ..celcius
+        };
+
+        assert_eq!(celcius.as_raw_parts()[2], 0b01000000);
+        assert_eq!(fahrenheit.as_raw_parts()[2], 0b01100000);
+    }
+
+    #[test]
+    fn when_shorthand_constructors_then_states_are_powered_with_defaults() {
+        let temperature = Temperature::Celcius(22);
+
+        let cool = EliosState::cool(temperature);
+        let heat = EliosState::heat(temperature);
+        let dry = EliosState::dry(temperature);
+        let fan = EliosState::fan_only();
+        let auto = EliosState::auto(temperature);
+
+        assert_eq!(
+            (cool.mode, cool.fan_speed),
+            (Mode::Cold, FanSpeed::Automatic)
+        );
+        assert_eq!(
+            (heat.mode, heat.fan_speed),
+            (Mode::Heat, FanSpeed::Automatic)
+        );
+        assert_eq!((dry.mode, dry.fan_speed), (Mode::Dry, FanSpeed::Off));
+        assert_eq!((fan.mode, fan.fan_speed), (Mode::Fan, FanSpeed::Automatic));
+        assert_eq!(
+            (auto.mode, auto.fan_speed),
+            (Mode::Automatic, FanSpeed::Off)
+        );
+
+        for state in [cool, heat, dry, fan, auto].iter() {
+            assert!(state.powered);
+            assert_eq!(EliosState::from_value(state.as_value()), Some(*state));
+        }
+    }
+
+    #[test]
+    fn given_every_valid_state_then_decoding_is_the_inverse_of_encoding() {
+        let states: Vec<EliosState> = EliosState::all_valid().collect();
+        let distinct: std::collections::HashSet<u64> =
+            states.iter().map(|state| state.as_value()).collect();
+
+        assert_eq!(distinct.len(), states.len());
+
+        for state in states {
+            assert_eq!(EliosState::from_value(state.as_value()), Some(state));
+        }
+    }
+
+    #[test]
+    fn when_temperature_step_count_then_every_setpoint_is_counted() {
+        assert_eq!(
+            EliosState::temperature_step_count(TemperatureUnit::Celcius),
+            14
+        );
+        assert_eq!(
+            EliosState::temperature_step_count(TemperatureUnit::Fahrenheit),
+            25
+        );
+    }
+
+    #[test]
+    fn given_every_fahrenheit_setpoint_then_frame_holds_a_valid_raw_code() {
+        for temp in MIN_FAHRENHEIT..=MAX_FAHRENHEIT {
+            let state = EliosState::cool(Temperature::Fahrenheit(temp));
+
+            assert_eq!(
+                state.as_raw_parts()[2],
+                0b01100000 | (temp - MIN_FAHRENHEIT)
+            );
+            assert_eq!(EliosState::from_value(state.as_value()), Some(state));
+        }
+    }
+
+    #[test]
+    fn given_base_state_then_sweep_covers_every_setpoint() {
+        let base = EliosState::heat(Temperature::Fahrenheit(70));
+
+        let sweep = EliosState::temperature_sweep(base);
+
+        assert_eq!(
+            sweep.len(),
+            EliosState::temperature_step_count(TemperatureUnit::Fahrenheit)
+        );
+        assert_eq!(
+            sweep[0].temperature,
+            Temperature::Fahrenheit(MIN_FAHRENHEIT)
+        );
+        for state in sweep {
+            assert_eq!(state.mode, base.mode);
+            assert_eq!(EliosState::from_value(state.as_value()), Some(state));
+        }
+    }
+
+    #[test]
+    fn given_fan_mode_base_then_sweep_is_empty() {
+        assert!(EliosState::temperature_sweep(EliosState::fan_only()).is_empty());
+    }
+
+    #[test]
+    fn given_adjacent_fahrenheit_setpoints_then_frames_never_collide() {
+        for temp in MIN_FAHRENHEIT..MAX_FAHRENHEIT {
+            assert_ne!(
+                EliosState::cool(Temperature::Fahrenheit(temp)).as_value(),
+                EliosState::cool(Temperature::Fahrenheit(temp + 1)).as_value()
+            );
+        }
+    }
+
+    #[test]
+    fn given_celcius_target_then_closest_step_in_each_unit_is_found() {
+        assert_eq!(
+            EliosState::closest_step_to_celsius(22.0, TemperatureUnit::Fahrenheit),
+            Temperature::Fahrenheit(72)
+        );
+        assert_eq!(
+            EliosState::closest_step_to_celsius(22.4, TemperatureUnit::Celcius),
+            Temperature::Celcius(22)
+        );
+        assert_eq!(
+            EliosState::closest_step_to_celsius(40.0, TemperatureUnit::Celcius),
+            Temperature::Celcius(MAX_CELCIUS)
+        );
+    }
+
     #[test]
     fn given_out_of_range_temperature_then_temperature_is_clamped() {
         let lower_min_celcius = EliosState::new(